@@ -0,0 +1,286 @@
+use crate::bean::RdbcOrmRow;
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::trans::{finish_transaction, CatchUnwind, RdbcTransactionFuture};
+use crate::{PageData, RdbcTransaction};
+use bmbp_sql::RdbcValue;
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::panic::resume_unwind;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use tokio::sync::{mpsc, oneshot};
+
+/// 事务传播方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Propagation {
+    /// 存在当前事务时加入，否则开启新事务
+    #[default]
+    Required,
+    /// 总是使用新的连接开启新事务，新事务结束后恢复当前事务
+    RequiresNew,
+    /// 存在当前事务时加入，否则以非事务方式执行
+    Supports,
+    /// 以非事务方式执行，存在当前事务时报错
+    Never,
+}
+
+tokio::task_local! {
+    /// 当前任务中各 RdbcOrm 实例开启的事务，按实例 id 区分，嵌套开启时内层作用域结束后恢复外层
+    static AMBIENT: HashMap<usize, AmbientTransaction>;
+}
+
+/// 在事务所有者一侧执行的语句调用
+type AmbientCall = Box<
+    dyn for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, ()> + Send,
+>;
+
+fn ambient_call<F>(f: F) -> AmbientCall
+where
+    F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, ()>
+        + Send
+        + 'static,
+{
+    Box::new(f)
+}
+
+/// 绑定到当前任务的事务
+/// 事务借用连接池中的连接，无法放入任务本地存储，语句经通道交给开启事务的一方执行
+#[derive(Clone)]
+pub(crate) struct AmbientTransaction {
+    orm_id: usize,
+    sender: mpsc::UnboundedSender<AmbientCall>,
+    rollback_only: Arc<AtomicBool>,
+}
+
+impl AmbientTransaction {
+    /// 当前任务中由指定 RdbcOrm 实例开启的事务
+    pub(crate) fn current(orm_id: usize) -> Option<AmbientTransaction> {
+        AMBIENT
+            .try_with(|ambients| ambients.get(&orm_id).cloned())
+            .ok()
+            .flatten()
+    }
+
+    /// 在保留当前任务中其它实例事务的前提下，将事务绑定到 fut
+    fn bind<Fut>(self, fut: Fut) -> impl Future<Output = Fut::Output>
+    where
+        Fut: Future,
+    {
+        let mut ambients = AMBIENT
+            .try_with(|ambients| ambients.clone())
+            .unwrap_or_default();
+        ambients.insert(self.orm_id, self);
+        AMBIENT.scope(ambients, fut)
+    }
+
+    /// 加入当前事务执行，失败时将事务标记为仅回滚
+    pub(crate) async fn join<Fut, R>(&self, fut: Fut) -> OrmResp<R>
+    where
+        Fut: Future<Output = OrmResp<R>>,
+    {
+        let result = CatchUnwind(Box::pin(fut)).await;
+        if !matches!(result, Ok(Ok(_))) {
+            self.rollback_only.store(true, Ordering::SeqCst);
+        }
+        match result {
+            Ok(rs) => rs,
+            Err(panic) => resume_unwind(panic),
+        }
+    }
+
+    pub(crate) async fn find_page_by_sql(
+        &self,
+        sql: String,
+        params: Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        self.run(move |trans| {
+            Box::pin(async move {
+                trans
+                    .find_page_by_sql(&sql, &params, page_num, page_size)
+                    .await
+            })
+        })
+        .await
+    }
+    pub(crate) async fn find_list_by_sql(
+        &self,
+        sql: String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        self.run(move |trans| Box::pin(async move { trans.find_list_by_sql(&sql, &params).await }))
+            .await
+    }
+    pub(crate) async fn find_one_by_sql(
+        &self,
+        sql: String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        self.run(move |trans| Box::pin(async move { trans.find_one_by_sql(&sql, &params).await }))
+            .await
+    }
+    pub(crate) async fn execute_sql(&self, sql: String, params: Vec<RdbcValue>) -> OrmResp<usize> {
+        self.run(move |trans| Box::pin(async move { trans.execute_sql(&sql, &params).await }))
+            .await
+    }
+    pub(crate) async fn execute_ddl(&self, sql: String, params: Vec<RdbcValue>) -> OrmResp<()> {
+        self.run(move |trans| Box::pin(async move { trans.execute_raw_ddl(&sql, params).await }))
+            .await
+    }
+
+    /// 交给事务所有者执行，失败时将事务标记为仅回滚
+    async fn run<F, R>(&self, f: F) -> OrmResp<R>
+    where
+        F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        let (reply, receiver) = oneshot::channel();
+        let call = ambient_call(move |trans| {
+            Box::pin(async move {
+                let _ = reply.send(f(trans).await);
+                Ok(())
+            })
+        });
+        let result = match self.sender.send(call) {
+            Ok(_) => receiver.await.unwrap_or_else(|_| Err(ambient_closed())),
+            Err(_) => Err(ambient_closed()),
+        };
+        if result.is_err() {
+            self.rollback_only.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+}
+
+enum AmbientStep<R> {
+    Done(R),
+    Call(AmbientCall),
+}
+
+/// 将事务绑定到当前任务后执行 fut，期间执行 fut 发来的语句，结束后提交或回滚
+pub(crate) async fn run_with_ambient<Fut, R>(
+    trans: &mut RdbcTransaction<'_>,
+    orm_id: usize,
+    fut: Fut,
+) -> OrmResp<R>
+where
+    Fut: Future<Output = OrmResp<R>>,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let rollback_only = Arc::new(AtomicBool::new(false));
+    let ambient = AmbientTransaction {
+        orm_id,
+        sender,
+        rollback_only: rollback_only.clone(),
+    };
+    let mut task = CatchUnwind(Box::pin(ambient.bind(fut)));
+    let result = loop {
+        let step = poll_fn(|cx| {
+            if let Poll::Ready(output) = Pin::new(&mut task).poll(cx) {
+                return Poll::Ready(AmbientStep::Done(output));
+            }
+            match receiver.poll_recv(cx) {
+                Poll::Ready(Some(call)) => Poll::Ready(AmbientStep::Call(call)),
+                _ => Poll::Pending,
+            }
+        })
+        .await;
+        match step {
+            AmbientStep::Done(output) => break output,
+            AmbientStep::Call(call) => {
+                let _ = call(&mut *trans).await;
+            }
+        }
+    };
+    let result = match result {
+        Ok(Ok(_)) if rollback_only.load(Ordering::SeqCst) => Ok(Err(OrmError::new(
+            OrmErrorKind::TransactionError,
+            "事务中的操作执行失败，事务已回滚".to_string(),
+        ))),
+        result => result,
+    };
+    finish_transaction(trans, result).await
+}
+
+pub(crate) fn never_in_transaction() -> OrmError {
+    OrmError::new(
+        OrmErrorKind::TransactionError,
+        "当前任务已存在事务，Never传播方式不能在事务中执行".to_string(),
+    )
+}
+
+fn ambient_closed() -> OrmError {
+    OrmError::new(
+        OrmErrorKind::TransactionError,
+        "当前任务绑定的事务已结束".to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ambient(orm_id: usize) -> (AmbientTransaction, mpsc::UnboundedReceiver<AmbientCall>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let ambient = AmbientTransaction {
+            orm_id,
+            sender,
+            rollback_only: Arc::new(AtomicBool::new(false)),
+        };
+        (ambient, receiver)
+    }
+
+    fn is_current(orm_id: usize, expected: &AmbientTransaction) -> bool {
+        AmbientTransaction::current(orm_id)
+            .is_some_and(|current| current.sender.same_channel(&expected.sender))
+    }
+
+    #[tokio::test]
+    async fn nested_orms_keep_their_own_transactions() {
+        let (outer, _outer_rx) = ambient(1);
+        let (inner, _inner_rx) = ambient(2);
+        let (nested, _nested_rx) = ambient(1);
+        assert!(AmbientTransaction::current(1).is_none());
+        outer
+            .clone()
+            .bind(async {
+                assert!(is_current(1, &outer));
+                assert!(AmbientTransaction::current(2).is_none());
+                inner
+                    .clone()
+                    .bind(async {
+                        assert!(is_current(1, &outer));
+                        assert!(is_current(2, &inner));
+                        nested
+                            .clone()
+                            .bind(async {
+                                assert!(is_current(1, &nested));
+                                assert!(is_current(2, &inner));
+                            })
+                            .await;
+                        assert!(is_current(1, &outer));
+                    })
+                    .await;
+                assert!(is_current(1, &outer));
+                assert!(AmbientTransaction::current(2).is_none());
+            })
+            .await;
+        assert!(AmbientTransaction::current(1).is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_statement_marks_rollback_only() {
+        let (ambient, receiver) = ambient(1);
+        drop(receiver);
+        let rs = ambient
+            .execute_sql("DELETE FROM t".to_string(), vec![])
+            .await;
+        assert!(rs.is_err());
+        assert!(ambient.rollback_only.load(Ordering::SeqCst));
+    }
+}
@@ -1,5 +1,7 @@
 mod row;
+mod row_mysql;
+mod row_oracle;
 mod row_pg;
+mod row_sqlite;
 
 pub use row::*;
-pub use row_pg::*;
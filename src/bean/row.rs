@@ -83,3 +83,18 @@ where
         self
     }
 }
+
+/// 行数据分页结果转换为业务类型分页结果
+pub(crate) fn convert_page_data<T>(row_page_data: PageData<RdbcOrmRow>) -> PageData<T>
+where
+    T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+{
+    PageData::<T> {
+        page_num: row_page_data.page_num,
+        page_size: row_page_data.page_size,
+        total: row_page_data.total,
+        data: row_page_data
+            .data
+            .map(|rows| rows.into_iter().map(T::from).collect()),
+    }
+}
@@ -0,0 +1,99 @@
+use crate::bean::row::RdbcOrmRow;
+use crate::error::{OrmError, OrmErrorKind};
+use bmbp_sql::RdbcValue;
+use chrono::NaiveDate;
+use mysql_async::consts::ColumnType;
+use mysql_async::{Row, Value};
+
+impl TryFrom<Row> for RdbcOrmRow {
+    type Error = OrmError;
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        let mut orm_row = RdbcOrmRow::new();
+        let columns = row.columns();
+        for (idx, col) in columns.iter().enumerate() {
+            let col_name = col.name_str().to_string();
+            orm_row.get_columns_mut().push(col_name.clone());
+            let col_type = col.column_type();
+            match row.as_ref(idx) {
+                None | Some(Value::NULL) => {}
+                Some(Value::Int(value)) => match col_type {
+                    ColumnType::MYSQL_TYPE_TINY | ColumnType::MYSQL_TYPE_SHORT => {
+                        orm_row
+                            .get_data_mut()
+                            .insert(col_name, RdbcValue::Int(*value as i32));
+                    }
+                    _ => {
+                        orm_row
+                            .get_data_mut()
+                            .insert(col_name, RdbcValue::BigInt(*value));
+                    }
+                },
+                // BIGINT UNSIGNED 超出 i64 范围时以文本返回
+                Some(Value::UInt(value)) => {
+                    let value = match i64::try_from(*value) {
+                        Ok(value) => RdbcValue::BigInt(value),
+                        Err(_) => RdbcValue::Varchar(value.to_string()),
+                    };
+                    orm_row.get_data_mut().insert(col_name, value);
+                }
+                Some(Value::Float(value)) => {
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Double(*value));
+                }
+                // RdbcValue::Double 为 f32，DOUBLE 转为文本以免丢失精度
+                Some(Value::Double(value)) => {
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value.to_string()));
+                }
+                // DECIMAL 以文本传输，原样保留避免金额等定点数丢失精度；
+                // BLOB/BINARY 等非 UTF-8 内容无法表示为文本，直接报错而不是替换为乱码
+                Some(Value::Bytes(bytes)) => {
+                    let value = String::from_utf8(bytes.clone()).map_err(|_| {
+                        OrmError::new(
+                            OrmErrorKind::DataError,
+                            format!("mysql列{}为二进制数据，暂不支持读取", col_name),
+                        )
+                    })?;
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value));
+                }
+                Some(Value::Date(year, month, day, hour, minute, second, micro)) => {
+                    let date_time = NaiveDate::from_ymd_opt(
+                        *year as i32,
+                        *month as u32,
+                        *day as u32,
+                    )
+                    .and_then(|d| {
+                        d.and_hms_micro_opt(*hour as u32, *minute as u32, *second as u32, *micro)
+                    });
+                    if let Some(value) = date_time {
+                        orm_row
+                            .get_data_mut()
+                            .insert(col_name, RdbcValue::DateTime(value));
+                    } else {
+                        tracing::warn!("mysql日期列解析失败: {}", col_name);
+                        orm_row.get_data_mut().insert(col_name, RdbcValue::Null);
+                    }
+                }
+                Some(Value::Time(negative, days, hours, minutes, seconds, micros)) => {
+                    let total_hours = *days * 24 + *hours as u32;
+                    let value = format!(
+                        "{}{:02}:{:02}:{:02}.{:06}",
+                        if *negative { "-" } else { "" },
+                        total_hours,
+                        minutes,
+                        seconds,
+                        micros
+                    );
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value));
+                }
+            }
+        }
+        Ok(orm_row)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::bean::row::RdbcOrmRow;
+use bb8_oracle::oracle::sql_type::OracleType;
+use bb8_oracle::oracle::Row;
+use bmbp_sql::RdbcValue;
+
+impl From<Row> for RdbcOrmRow {
+    fn from(row: Row) -> Self {
+        let mut orm_row = RdbcOrmRow::new();
+        for (idx, col) in row.column_info().iter().enumerate() {
+            let col_name = col.name().to_string();
+            orm_row.get_columns_mut().push(col_name.clone());
+            let col_value = match col.oracle_type() {
+                OracleType::Varchar2(_)
+                | OracleType::NVarchar2(_)
+                | OracleType::Char(_)
+                | OracleType::NChar(_)
+                | OracleType::Long
+                | OracleType::CLOB
+                | OracleType::NCLOB
+                | OracleType::Rowid => read_text(&row, idx, &col_name),
+                // 整数值（含未声明精度的 COUNT(1) 等）按整数返回，
+                // 小数或超出 i64 范围时以数据库格式化的文本返回，避免转为浮点数丢失精度
+                OracleType::Number(_, _) | OracleType::Int64 => {
+                    match row.get::<_, Option<i64>>(idx) {
+                        Ok(value) => value.map(RdbcValue::BigInt),
+                        Err(_) => read_text(&row, idx, &col_name),
+                    }
+                }
+                OracleType::Float(_) | OracleType::BinaryFloat | OracleType::BinaryDouble => {
+                    read_text(&row, idx, &col_name)
+                }
+                OracleType::Date
+                | OracleType::Timestamp(_)
+                | OracleType::TimestampTZ(_)
+                | OracleType::TimestampLTZ(_) => {
+                    match row.get::<_, Option<chrono::NaiveDateTime>>(idx) {
+                        Ok(value) => value.map(RdbcValue::DateTime),
+                        Err(_) => read_text(&row, idx, &col_name),
+                    }
+                }
+                col_type => {
+                    tracing::warn!("oracle数据库暂未支持的列类型: {:#?}", col_type);
+                    Some(RdbcValue::Null)
+                }
+            };
+            if let Some(value) = col_value {
+                orm_row.get_data_mut().insert(col_name, value);
+            }
+        }
+        orm_row
+    }
+}
+
+/// 读取文本值，按类型转换失败时（如超出 i64 范围的 NUMBER(38)）以文本返回，避免数据被丢弃
+fn read_text(row: &Row, idx: usize, col_name: &str) -> Option<RdbcValue> {
+    match row.get::<_, Option<String>>(idx) {
+        Ok(value) => value.map(RdbcValue::Varchar),
+        Err(err) => {
+            tracing::warn!("oracle列{}读取失败: {}", col_name, err);
+            Some(RdbcValue::Null)
+        }
+    }
+}
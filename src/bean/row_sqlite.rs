@@ -0,0 +1,67 @@
+use crate::bean::row::RdbcOrmRow;
+use bmbp_sql::RdbcValue;
+use rusqlite::types::ValueRef;
+use rusqlite::Row;
+
+impl<'stmt> From<&Row<'stmt>> for RdbcOrmRow {
+    fn from(row: &Row<'stmt>) -> Self {
+        let mut orm_row = RdbcOrmRow::new();
+        let stmt = row.as_ref();
+        for idx in 0..stmt.column_count() {
+            let col_name = match stmt.column_name(idx) {
+                Ok(name) => name.to_string(),
+                Err(_) => continue,
+            };
+            orm_row.get_columns_mut().push(col_name.clone());
+            match row.get_ref(idx) {
+                Ok(ValueRef::Null) | Err(_) => {}
+                Ok(ValueRef::Integer(value)) => {
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::BigInt(value));
+                }
+                // RdbcValue::Double 为 f32，REAL 与 mysql DOUBLE 一致转为文本以免丢失精度
+                Ok(ValueRef::Real(value)) => {
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value.to_string()));
+                }
+                Ok(ValueRef::Text(value)) => {
+                    let value = String::from_utf8_lossy(value).to_string();
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value));
+                }
+                Ok(ValueRef::Blob(value)) => {
+                    tracing::warn!("sqlite二进制列按文本读取: {}", col_name);
+                    let value = String::from_utf8_lossy(value).to_string();
+                    orm_row
+                        .get_data_mut()
+                        .insert(col_name, RdbcValue::Varchar(value));
+                }
+            }
+        }
+        orm_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn real_keeps_full_precision() {
+        let conn = Connection::open_in_memory().unwrap();
+        let row = conn
+            .query_row("SELECT 0.1 + 0.2 AS v, 3 AS n", [], |row| {
+                Ok(RdbcOrmRow::from(row))
+            })
+            .unwrap();
+        assert_eq!(
+            row.get_data().get("v"),
+            Some(&RdbcValue::Varchar((0.1f64 + 0.2).to_string()))
+        );
+        assert_eq!(row.get_data().get("n"), Some(&RdbcValue::BigInt(3)));
+    }
+}
@@ -1,2 +1,9 @@
+mod mysql;
+mod oracle;
 mod pg;
+mod sqlite;
+mod util;
+pub use mysql::*;
+pub use oracle::*;
 pub use pg::*;
+pub use sqlite::*;
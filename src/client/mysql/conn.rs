@@ -0,0 +1,321 @@
+use crate::client::mysql::pool::RdbcMysqlConnectionManager;
+use crate::client::util::{
+    ddl_partial_error, page_limit_offset, read_count, transaction_completed,
+};
+use crate::error::OrmResp;
+use crate::{IsolationLevel, PageData, RdbcOrmRow, RdbcTransaction, TransactionOptions};
+use bb8::PooledConnection;
+use bmbp_sql::{
+    render_delete, render_insert, render_query, render_update, DataBase, RdbcDeleteWrapper,
+    RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper, RdbcValue,
+};
+use chrono::{Datelike, Timelike};
+use mysql_async::prelude::Queryable;
+use mysql_async::{IsolationLevel as MysqlIsolationLevel, Params, Row, Transaction, TxOpts, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub struct RdbcMysqlConn<'a> {
+    pub conn: PooledConnection<'a, RdbcMysqlConnectionManager>,
+    pub(crate) validation_query: Arc<str>,
+}
+impl<'a> RdbcMysqlConn<'a> {
+    pub(crate) async fn validate(&mut self) -> OrmResp<()> {
+        self.conn.query_drop(&*self.validation_query).await?;
+        Ok(())
+    }
+    pub async fn get_transaction(
+        &mut self,
+        options: &TransactionOptions,
+    ) -> OrmResp<RdbcTransaction<'_>> {
+        let mut tx_opts = TxOpts::default();
+        tx_opts
+            .with_isolation_level(options.isolation_level.map(|level| match level {
+                IsolationLevel::ReadCommitted => MysqlIsolationLevel::ReadCommitted,
+                IsolationLevel::RepeatableRead => MysqlIsolationLevel::RepeatableRead,
+                IsolationLevel::Serializable => MysqlIsolationLevel::Serializable,
+            }))
+            .with_readonly(options.read_only.then_some(true));
+        let broken = self.conn.broken.clone();
+        let mut trans = self.conn.start_transaction(tx_opts).await?;
+        let reset_timeout = match options.statement_timeout {
+            Some(timeout) => {
+                let sql = format!("SET SESSION max_execution_time = {}", timeout.as_millis());
+                trans.query_drop(sql).await?;
+                true
+            }
+            None => false,
+        };
+        let conn = RdbcMysqlTransaction {
+            trans: Some(trans),
+            reset_timeout,
+            broken,
+        };
+        Ok(RdbcTransaction::Mysql(conn))
+    }
+    pub(crate) async fn find_page_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Mysql);
+        self.find_page_by_sql_params(&sql, &params, page_num, page_size)
+            .await
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Mysql);
+        self.find_list_by_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn find_one_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Mysql);
+        self.find_one_by_sql_params(&sql, &params).await
+    }
+
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let rows: Vec<Row> = self
+            .conn
+            .exec(sql.as_str(), to_mysql_params(params))
+            .await?;
+        rows.into_iter().map(RdbcOrmRow::try_from).collect()
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let row: Option<Row> = self
+            .conn
+            .exec_first(sql.as_str(), to_mysql_params(params))
+            .await?;
+        row.map(RdbcOrmRow::try_from).transpose()
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_insert_by_wrapper(
+        &mut self,
+        insert: &RdbcInsertWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_insert(insert, DataBase::Mysql);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_update_by_wrapper(
+        &mut self,
+        update: &RdbcUpdateWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_update(update, DataBase::Mysql);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_delete_by_wrapper(
+        &mut self,
+        delete: &RdbcDeleteWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_delete(delete, DataBase::Mysql);
+        self.execute_sql_params(&sql, &params).await
+    }
+
+    /// MySQL 的 DDL 会隐式提交，逐条执行并在失败时说明已生效的语句
+    pub(crate) async fn execute_ddl_statements(&mut self, statements: &Vec<String>) -> OrmResp<()> {
+        for (index, statement) in statements.iter().enumerate() {
+            if let Err(err) = self.conn.query_drop(statement.as_str()).await {
+                return Err(ddl_partial_error(index, statements.len(), err.into()));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.conn
+            .exec_drop(sql.as_str(), to_mysql_params(params))
+            .await?;
+        Ok(self.conn.affected_rows() as usize)
+    }
+}
+
+/// 恢复会话默认的语句超时，未正常结束的事务所在的连接由连接池丢弃
+const RESET_TIMEOUT_SQL: &str = "SET SESSION max_execution_time = DEFAULT";
+
+pub struct RdbcMysqlTransaction<'a> {
+    pub trans: Option<Transaction<'a>>,
+    /// 开启事务时设置了会话级语句超时，结束前需恢复
+    reset_timeout: bool,
+    /// 所在连接的损坏标记
+    broken: Arc<AtomicBool>,
+}
+/// 未提交或回滚的事务无法在此异步回滚及恢复会话设置，标记连接损坏，归还时由连接池丢弃
+impl Drop for RdbcMysqlTransaction<'_> {
+    fn drop(&mut self) {
+        if self.trans.is_some() {
+            self.broken.store(true, Ordering::SeqCst);
+        }
+    }
+}
+impl<'a> RdbcMysqlTransaction<'a> {
+    pub async fn commit(&mut self) -> OrmResp<()> {
+        if let Some(mut trans) = self.trans.take() {
+            if self.reset_timeout {
+                trans.query_drop(RESET_TIMEOUT_SQL).await?;
+            }
+            trans.commit().await?;
+            Ok(())
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    pub async fn rollback(&mut self) -> OrmResp<()> {
+        if let Some(mut trans) = self.trans.take() {
+            if self.reset_timeout {
+                trans.query_drop(RESET_TIMEOUT_SQL).await?;
+            }
+            trans.rollback().await?;
+            Ok(())
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    fn trans(&mut self) -> OrmResp<&mut Transaction<'a>> {
+        self.trans.as_mut().ok_or_else(transaction_completed)
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let rows: Vec<Row> = self
+            .trans()?
+            .exec(sql.as_str(), to_mysql_params(params))
+            .await?;
+        rows.into_iter().map(RdbcOrmRow::try_from).collect()
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let row: Option<Row> = self
+            .trans()?
+            .exec_first(sql.as_str(), to_mysql_params(params))
+            .await?;
+        row.map(RdbcOrmRow::try_from).transpose()
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let trans = self.trans()?;
+        trans
+            .exec_drop(sql.as_str(), to_mysql_params(params))
+            .await?;
+        Ok(trans.affected_rows() as usize)
+    }
+    /// 执行无参数的控制语句，如保存点；保存点语句不能走预处理协议
+    pub(crate) async fn execute_command(&mut self, sql: &str) -> OrmResp<()> {
+        self.trans()?.query_drop(sql).await?;
+        Ok(())
+    }
+}
+
+/// RdbcValue 转换为 mysql 位置参数
+pub(crate) fn to_mysql_params(params: &Vec<RdbcValue>) -> Params {
+    let values = params.iter().map(to_mysql_value).collect::<Vec<Value>>();
+    Params::from(values)
+}
+
+fn to_mysql_value(value: &RdbcValue) -> Value {
+    match value {
+        RdbcValue::Varchar(v) => Value::from(v.as_str()),
+        RdbcValue::Int(v) => Value::from(*v),
+        RdbcValue::BigInt(v) => Value::from(*v),
+        RdbcValue::Double(v) => Value::from(*v),
+        RdbcValue::Boolean(v) => Value::from(*v),
+        RdbcValue::DateTime(v) => Value::Date(
+            v.year() as u16,
+            v.month() as u8,
+            v.day() as u8,
+            v.hour() as u8,
+            v.minute() as u8,
+            v.second() as u8,
+            v.nanosecond() / 1000,
+        ),
+        RdbcValue::Null => Value::NULL,
+        _ => {
+            tracing::warn!("mysql数据库暂未支持的参数类型: {:#?}", value);
+            Value::NULL
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod conn;
+mod pool;
+
+pub use conn::*;
+pub use pool::*;
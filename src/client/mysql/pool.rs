@@ -0,0 +1,171 @@
+use crate::client::mysql::conn::RdbcMysqlConn;
+use crate::client::util::{
+    bb8_builder, bb8_health, bb8_in_use, connect_with_retry, tls_error, validation_query, LastError,
+};
+use crate::error::{OrmError, OrmResp};
+use crate::{PoolHealth, RdbcConn, RdbcDataSource, RdbcPool, TlsConfig, TlsMode};
+use bb8::{ManageConnection, Pool};
+use mysql_async::prelude::Queryable;
+use mysql_async::{ClientIdentity, Conn, OptsBuilder, SslOpts};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 已建立的 MySQL 连接，事务未正常结束时标记为已损坏，归还时由连接池丢弃
+pub struct RdbcMysqlConnection {
+    conn: Conn,
+    pub(crate) broken: Arc<AtomicBool>,
+}
+impl Deref for RdbcMysqlConnection {
+    type Target = Conn;
+
+    fn deref(&self) -> &Self::Target {
+        &self.conn
+    }
+}
+impl DerefMut for RdbcMysqlConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.conn
+    }
+}
+
+/// 使用 mysql_async 建立连接，由 bb8 管理连接池
+/// 每次建立连接前按数据源的密码来源读取密码
+pub struct RdbcMysqlConnectionManager {
+    opts: OptsBuilder,
+    data_source: Arc<RdbcDataSource>,
+    validation_query: Arc<str>,
+}
+impl RdbcMysqlConnectionManager {
+    pub fn new(
+        opts: OptsBuilder,
+        data_source: Arc<RdbcDataSource>,
+        validation_query: Arc<str>,
+    ) -> Self {
+        RdbcMysqlConnectionManager {
+            opts,
+            data_source,
+            validation_query,
+        }
+    }
+}
+
+impl ManageConnection for RdbcMysqlConnectionManager {
+    type Connection = RdbcMysqlConnection;
+    type Error = OrmError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        let opts = self
+            .opts
+            .clone()
+            .pass(Some(self.data_source.resolve_password()?));
+        let conn = Conn::new(opts).await?;
+        Ok(RdbcMysqlConnection {
+            conn,
+            broken: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.query_drop(&*self.validation_query).await?;
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        conn.broken.load(Ordering::SeqCst)
+    }
+}
+
+pub struct RdbcMysqlPool {
+    datasource: Arc<RdbcDataSource>,
+    pool: Pool<RdbcMysqlConnectionManager>,
+    validation_query: Arc<str>,
+    last_error: LastError,
+}
+impl RdbcMysqlPool {
+    pub(crate) async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
+        let conn_rs = self.pool.get().await;
+        match conn_rs {
+            Ok(conn) => {
+                let conn = RdbcMysqlConn {
+                    conn,
+                    validation_query: self.validation_query.clone(),
+                };
+                Ok(RdbcConn::Mysql(conn))
+            }
+            Err(err) => {
+                let err = OrmError::from(err);
+                self.last_error.set(&err);
+                Err(err)
+            }
+        }
+    }
+    pub(crate) fn in_use(&self) -> usize {
+        bb8_in_use(&self.pool.state())
+    }
+    pub(crate) fn health(&self) -> PoolHealth {
+        bb8_health(
+            self.pool.state(),
+            &self.datasource.pool_config,
+            &self.last_error,
+        )
+    }
+}
+
+pub async fn build_mysql_pool(data_source: Arc<RdbcDataSource>) -> OrmResp<RdbcPool> {
+    let pool_config = &data_source.pool_config;
+    pool_config.validate()?;
+    let mut opts = OptsBuilder::default()
+        .ip_or_hostname(data_source.host.clone())
+        .tcp_port(data_source.port)
+        .user(Some(data_source.user.clone()))
+        .db_name(Some(data_source.db_name.clone()))
+        .ssl_opts(ssl_opts(&data_source.tls)?);
+    if !data_source.charset.is_empty() {
+        opts = opts.init(vec![format!("SET NAMES {}", data_source.charset)]);
+    }
+    let validation_query = validation_query(&data_source);
+    let last_error = LastError::default();
+    let pool = connect_with_retry(&pool_config.connect_retry, || async {
+        let manage = RdbcMysqlConnectionManager::new(
+            opts.clone(),
+            data_source.clone(),
+            validation_query.clone(),
+        );
+        bb8_builder(pool_config)
+            .error_sink(Box::new(last_error.clone()))
+            .build(manage)
+            .await
+            .map_err(OrmError::into_conn_error)
+    })
+    .await?;
+    Ok(RdbcPool::Mysql(RdbcMysqlPool {
+        datasource: data_source.clone(),
+        pool,
+        validation_query,
+        last_error,
+    }))
+}
+
+/// 按 TLS 模式创建 SSL 配置，客户端证书为 PKCS#12 文件
+fn ssl_opts(tls: &TlsConfig) -> OrmResp<Option<SslOpts>> {
+    if tls.mode == TlsMode::Disable {
+        return Ok(None);
+    }
+    if tls.client_key.is_some() {
+        return Err(tls_error(
+            "MySQL客户端证书需为包含私钥的PKCS#12文件，client_key不需要配置",
+        ));
+    }
+    let verify_cert = matches!(tls.mode, TlsMode::VerifyCa | TlsMode::VerifyFull);
+    let mut opts = SslOpts::default()
+        .with_danger_accept_invalid_certs(!verify_cert)
+        .with_danger_skip_domain_validation(tls.mode != TlsMode::VerifyFull);
+    if let Some(ca_cert) = &tls.ca_cert {
+        opts = opts.with_root_certs(vec![ca_cert.clone().into()]);
+    }
+    if let Some(client_cert) = &tls.client_cert {
+        opts = opts.with_client_identity(Some(ClientIdentity::new(client_cert.clone().into())));
+    }
+    Ok(Some(opts))
+}
@@ -0,0 +1,336 @@
+use crate::client::oracle::pool::RdbcOracleConnectionManager;
+use crate::client::util::{
+    block_in_place, ddl_partial_error, page_limit_offset, read_count, transaction_completed,
+};
+use crate::error::OrmResp;
+use crate::{IsolationLevel, PageData, RdbcOrmRow, RdbcTransaction, TransactionOptions};
+use bb8::PooledConnection;
+use bb8_oracle::oracle::sql_type::ToSql;
+use bb8_oracle::oracle::Connection;
+use bmbp_sql::{
+    render_delete, render_insert, render_query, render_update, DataBase, RdbcDeleteWrapper,
+    RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper, RdbcValue,
+};
+use std::sync::Arc;
+
+pub struct RdbcOracleConn<'a> {
+    pub conn: PooledConnection<'a, RdbcOracleConnectionManager>,
+    pub(crate) validation_query: Arc<str>,
+}
+impl<'a> RdbcOracleConn<'a> {
+    pub(crate) async fn validate(&mut self) -> OrmResp<()> {
+        let conn: &Connection = &self.conn;
+        block_in_place(|| conn.query_row(&self.validation_query, &[]))?;
+        Ok(())
+    }
+    pub async fn get_transaction(
+        &mut self,
+        options: &TransactionOptions,
+    ) -> OrmResp<RdbcTransaction<'_>> {
+        let conn: &mut Connection = &mut self.conn;
+        // 只读事务已提供事务级读一致性，不能再指定隔离级别
+        let set_sql = match (options.read_only, options.isolation_level) {
+            (true, _) => Some("SET TRANSACTION READ ONLY"),
+            (false, Some(IsolationLevel::ReadCommitted)) => {
+                Some("SET TRANSACTION ISOLATION LEVEL READ COMMITTED")
+            }
+            (false, Some(_)) => Some("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE"),
+            (false, None) => None,
+        };
+        conn.set_autocommit(false);
+        let mut trans = RdbcOracleTransaction {
+            conn: Some(conn),
+            call_timeout: options.statement_timeout.is_some(),
+        };
+        if let Some(sql) = set_sql {
+            trans.execute_command(sql).await?;
+        }
+        if let Some(timeout) = options.statement_timeout {
+            trans.conn()?.set_call_timeout(Some(timeout))?;
+        }
+        Ok(RdbcTransaction::Oracle(trans))
+    }
+    pub(crate) async fn find_page_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Oracle);
+        self.find_page_by_sql_params(&sql, &params, page_num, page_size)
+            .await
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) page_tmp OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            sql, offset, limit
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Oracle);
+        self.find_list_by_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn find_one_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Oracle);
+        self.find_one_by_sql_params(&sql, &params).await
+    }
+
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        query_rows(&self.conn, sql, params, None)
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let rows = query_rows(&self.conn, sql, params, Some(1))?;
+        Ok(rows.into_iter().next())
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS \"count\" FROM ({}) count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_insert_by_wrapper(
+        &mut self,
+        insert: &RdbcInsertWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_insert(insert, DataBase::Oracle);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_update_by_wrapper(
+        &mut self,
+        update: &RdbcUpdateWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_update(update, DataBase::Oracle);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_delete_by_wrapper(
+        &mut self,
+        delete: &RdbcDeleteWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_delete(delete, DataBase::Oracle);
+        self.execute_sql_params(&sql, &params).await
+    }
+
+    /// Oracle 的 DDL 会隐式提交，逐条执行并在失败时说明已生效的语句
+    pub(crate) async fn execute_ddl_statements(&mut self, statements: &Vec<String>) -> OrmResp<()> {
+        let conn: &Connection = &self.conn;
+        for (index, statement) in statements.iter().enumerate() {
+            if let Err(err) = block_in_place(|| conn.execute(statement.as_str(), &[])) {
+                return Err(ddl_partial_error(index, statements.len(), err.into()));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        execute_rows(&self.conn, sql, params)
+    }
+}
+
+pub struct RdbcOracleTransaction<'a> {
+    pub conn: Option<&'a mut Connection>,
+    /// 开启事务时设置了调用超时，结束后需清除
+    call_timeout: bool,
+}
+impl<'a> RdbcOracleTransaction<'a> {
+    pub async fn commit(&mut self) -> OrmResp<()> {
+        if let Some(conn) = self.conn.take() {
+            let rs = block_in_place(|| conn.commit());
+            restore_session(conn, self.call_timeout);
+            rs?;
+            Ok(())
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    pub async fn rollback(&mut self) -> OrmResp<()> {
+        if let Some(conn) = self.conn.take() {
+            let rs = block_in_place(|| conn.rollback());
+            restore_session(conn, self.call_timeout);
+            rs?;
+            Ok(())
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    fn conn(&self) -> OrmResp<&Connection> {
+        match self.conn.as_deref() {
+            Some(conn) => Ok(conn),
+            None => Err(transaction_completed()),
+        }
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) page_tmp OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+            sql, offset, limit
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        query_rows(self.conn()?, sql, params, None)
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let rows = query_rows(self.conn()?, sql, params, Some(1))?;
+        Ok(rows.into_iter().next())
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS \"count\" FROM ({}) count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        execute_rows(self.conn()?, sql, params)
+    }
+    /// 执行无参数的控制语句，如保存点
+    pub(crate) async fn execute_command(&mut self, sql: &str) -> OrmResp<()> {
+        execute_rows(self.conn()?, &sql.to_string(), &vec![])?;
+        Ok(())
+    }
+}
+
+impl<'a> Drop for RdbcOracleTransaction<'a> {
+    /// 未提交的事务在释放时回滚，并恢复连接的自动提交
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let call_timeout = self.call_timeout;
+            block_in_place(|| {
+                if let Err(err) = conn.rollback() {
+                    tracing::warn!("oracle事务回滚失败: {}", err);
+                }
+                restore_session(conn, call_timeout);
+            });
+        }
+    }
+}
+
+/// 事务结束后恢复连接的自动提交并清除调用超时
+fn restore_session(conn: &mut Connection, call_timeout: bool) {
+    conn.set_autocommit(true);
+    if call_timeout {
+        if let Err(err) = conn.set_call_timeout(None) {
+            tracing::warn!("oracle清除调用超时失败: {}", err);
+        }
+    }
+}
+
+pub(crate) fn query_rows(
+    conn: &Connection,
+    sql: &String,
+    params: &Vec<RdbcValue>,
+    limit: Option<usize>,
+) -> OrmResp<Vec<RdbcOrmRow>> {
+    let values = to_oracle_values(params);
+    let oracle_params = values.iter().map(|v| v.as_ref()).collect::<Vec<_>>();
+    block_in_place(|| {
+        let rows = conn.query(sql.as_str(), oracle_params.as_slice())?;
+        let mut list = Vec::new();
+        for row in rows {
+            list.push(RdbcOrmRow::from(row?));
+            if limit.map(|l| list.len() >= l).unwrap_or(false) {
+                break;
+            }
+        }
+        Ok(list)
+    })
+}
+
+pub(crate) fn execute_rows(
+    conn: &Connection,
+    sql: &String,
+    params: &Vec<RdbcValue>,
+) -> OrmResp<usize> {
+    let values = to_oracle_values(params);
+    let oracle_params = values.iter().map(|v| v.as_ref()).collect::<Vec<_>>();
+    block_in_place(|| {
+        let stmt = conn.execute(sql.as_str(), oracle_params.as_slice())?;
+        Ok(stmt.row_count()? as usize)
+    })
+}
+
+/// RdbcValue 转换为 oracle 绑定参数
+pub(crate) fn to_oracle_values(params: &Vec<RdbcValue>) -> Vec<Box<dyn ToSql>> {
+    params.iter().map(to_oracle_value).collect()
+}
+
+fn to_oracle_value(value: &RdbcValue) -> Box<dyn ToSql> {
+    match value {
+        RdbcValue::Varchar(v) => Box::new(v.clone()),
+        RdbcValue::Int(v) => Box::new(*v),
+        RdbcValue::BigInt(v) => Box::new(*v),
+        RdbcValue::Double(v) => Box::new(*v as f64),
+        RdbcValue::Boolean(v) => Box::new(if *v { 1i32 } else { 0i32 }),
+        RdbcValue::DateTime(v) => Box::new(*v),
+        RdbcValue::Null => Box::new(Option::<String>::None),
+        _ => {
+            tracing::warn!("oracle数据库暂未支持的参数类型: {:#?}", value);
+            Box::new(Option::<String>::None)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::client::oracle::conn::RdbcOracleConn;
+use crate::client::util::{
+    bb8_builder, bb8_health, bb8_in_use, block_in_place, connect_with_retry, validation_query,
+    LastError,
+};
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{PoolHealth, RdbcConn, RdbcDataSource, RdbcPool};
+use bb8::{ManageConnection, Pool};
+use bb8_oracle::oracle::{ConnStatus, Connection, Connector};
+use std::sync::Arc;
+
+/// 参照 bb8_oracle 建立连接，使用配置的检测语句检测连接，并剔除已断开的连接
+/// 每次建立连接前按数据源的密码来源读取密码
+pub struct RdbcOracleConnectionManager {
+    connect_string: String,
+    data_source: Arc<RdbcDataSource>,
+    validation_query: Arc<str>,
+}
+impl RdbcOracleConnectionManager {
+    pub fn new(
+        connect_string: String,
+        data_source: Arc<RdbcDataSource>,
+        validation_query: Arc<str>,
+    ) -> Self {
+        RdbcOracleConnectionManager {
+            connect_string,
+            data_source,
+            validation_query,
+        }
+    }
+}
+
+impl ManageConnection for RdbcOracleConnectionManager {
+    type Connection = Connection;
+    type Error = OrmError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        let password = self.data_source.resolve_password()?;
+        let connector = Connector::new(
+            self.data_source.user.as_str(),
+            password,
+            self.connect_string.as_str(),
+        );
+        // 与 bb8_oracle 一致，在阻塞线程中建立连接
+        match tokio::task::spawn_blocking(move || connector.connect()).await {
+            Ok(conn_rs) => Ok(conn_rs?),
+            Err(err) => Err(OrmError::from(bb8_oracle::Error::Panic(err))),
+        }
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        block_in_place(|| conn.query_row(&self.validation_query, &[]))?;
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        !matches!(conn.status(), Ok(ConnStatus::Normal))
+    }
+}
+
+pub struct RdbcOraclePool {
+    datasource: Arc<RdbcDataSource>,
+    pool: Pool<RdbcOracleConnectionManager>,
+    validation_query: Arc<str>,
+    last_error: LastError,
+}
+impl RdbcOraclePool {
+    pub(crate) async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
+        let conn_rs = self.pool.get().await;
+        match conn_rs {
+            Ok(mut conn) => {
+                // 连接池外的语句逐条提交，事务内由 RdbcOracleTransaction 关闭自动提交
+                conn.set_autocommit(true);
+                let conn = RdbcOracleConn {
+                    conn,
+                    validation_query: self.validation_query.clone(),
+                };
+                Ok(RdbcConn::Oracle(conn))
+            }
+            Err(err) => {
+                let err = OrmError::from(err);
+                self.last_error.set(&err);
+                Err(err)
+            }
+        }
+    }
+    pub(crate) fn in_use(&self) -> usize {
+        bb8_in_use(&self.pool.state())
+    }
+    pub(crate) fn health(&self) -> PoolHealth {
+        bb8_health(
+            self.pool.state(),
+            &self.datasource.pool_config,
+            &self.last_error,
+        )
+    }
+}
+
+/// 构建 oracle 连接池，db_name 为服务名，按 //host:port/service_name 方式连接
+/// 连接超时通过 Easy Connect 的 connect_timeout 参数设置，需要 19c 及以上客户端
+pub async fn build_oracle_pool(data_source: Arc<RdbcDataSource>) -> OrmResp<RdbcPool> {
+    let pool_config = &data_source.pool_config;
+    pool_config.validate()?;
+    let mut connect_string = format!(
+        "//{}:{}/{}",
+        data_source.host, data_source.port, data_source.db_name
+    );
+    if let Some(timeout) = pool_config.connect_timeout {
+        connect_string = format!(
+            "{}?connect_timeout={}",
+            connect_string,
+            timeout.as_secs().max(1)
+        );
+    }
+    let validation_query = validation_query(&data_source);
+    let last_error = LastError::default();
+    let pool = connect_with_retry(&pool_config.connect_retry, || async {
+        let manage = RdbcOracleConnectionManager::new(
+            connect_string.clone(),
+            data_source.clone(),
+            validation_query.clone(),
+        );
+        bb8_builder(pool_config)
+            .error_sink(Box::new(last_error.clone()))
+            .build(manage)
+            .await
+            .map_err(|err| OrmError::new(OrmErrorKind::ConnError, err.to_string()))
+    })
+    .await?;
+    Ok(RdbcPool::Oracle(RdbcOraclePool {
+        datasource: data_source.clone(),
+        pool,
+        validation_query,
+        last_error,
+    }))
+}
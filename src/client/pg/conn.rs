@@ -1,23 +1,46 @@
-use crate::error::{OrmError, OrmErrorKind, OrmResp};
-use crate::{PageData, RdbcOrmRow, RdbcTransaction};
+use crate::client::pg::pool::RdbcPostgresConnectionManager;
+use crate::client::util::{page_limit_offset, read_count, transaction_completed};
+use crate::error::OrmResp;
+use crate::{IsolationLevel, PageData, RdbcOrmRow, RdbcTransaction, TransactionOptions};
 use bb8::PooledConnection;
-use bb8_postgres::PostgresConnectionManager;
 use bmbp_sql::{
     render_delete, render_insert, render_query, render_update, DataBase, RdbcDeleteWrapper,
     RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper, RdbcValue,
 };
+use std::sync::Arc;
 use tokio_postgres::types::ToSql;
-use tokio_postgres::{NoTls, Transaction};
+use tokio_postgres::{IsolationLevel as PgIsolationLevel, Transaction};
 
 pub struct RdbcPostgresConn<'a> {
-    pub conn: PooledConnection<'a, PostgresConnectionManager<NoTls>>,
+    pub conn: PooledConnection<'a, RdbcPostgresConnectionManager>,
+    pub(crate) validation_query: Arc<str>,
 }
 impl<'a> RdbcPostgresConn<'a> {
     pub(crate) async fn validate(&mut self) -> OrmResp<()> {
+        self.conn.simple_query(&self.validation_query).await?;
         Ok(())
     }
-    pub async fn get_transaction(&mut self) -> OrmResp<RdbcTransaction> {
-        let trans = self.conn.transaction().await?;
+    pub async fn get_transaction(
+        &mut self,
+        options: &TransactionOptions,
+    ) -> OrmResp<RdbcTransaction<'_>> {
+        let mut builder = self
+            .conn
+            .build_transaction()
+            .read_only(options.read_only)
+            .deferrable(options.deferrable);
+        if let Some(level) = options.isolation_level {
+            builder = builder.isolation_level(match level {
+                IsolationLevel::ReadCommitted => PgIsolationLevel::ReadCommitted,
+                IsolationLevel::RepeatableRead => PgIsolationLevel::RepeatableRead,
+                IsolationLevel::Serializable => PgIsolationLevel::Serializable,
+            });
+        }
+        let trans = builder.start().await?;
+        if let Some(timeout) = options.statement_timeout {
+            let sql = format!("SET LOCAL statement_timeout = {}", timeout.as_millis());
+            trans.batch_execute(sql.as_str()).await?;
+        }
         let conn = RdbcPostgresTransaction { trans: Some(trans) };
         Ok(RdbcTransaction::Postgres(conn))
     }
@@ -28,154 +51,99 @@ impl<'a> RdbcPostgresConn<'a> {
         page_size: usize,
     ) -> OrmResp<PageData<RdbcOrmRow>> {
         let (sql, params) = render_query(query, DataBase::Postgres);
+        self.find_page_by_sql_params(&sql, &params, page_num, page_size)
+            .await
+    }
+    pub(crate) async fn find_list_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Postgres);
+        self.find_list_by_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn find_one_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Postgres);
+        self.find_one_by_sql_params(&sql, &params).await
+    }
+
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
         let pg_prams = params
             .iter()
             .map(|v| v as &(dyn ToSql + Sync))
             .collect::<Vec<_>>();
-        let mut page_data = PageData::<RdbcOrmRow> {
-            page_num: page_num.clone(),
-            page_size: page_size.clone(),
-            total: 0,
-            data: None,
-        };
-
-        match self.find_count_by_sql_pg_params(&sql, &pg_prams).await {
-            Ok(count) => {
-                page_data.total = count;
-            }
-            Err(e) => {
-                return Err(OrmError {
-                    kind: OrmErrorKind::SqlError,
-                    msg: e.to_string(),
-                });
-            }
-        }
-        let new_page_num = {
-            if page_num > 1 {
-                page_num
-            } else {
-                1
-            }
-        };
-        let new_page_size = {
-            if page_size > 0 {
-                page_size
-            } else {
-                10
-            }
-        };
+        let total = self.find_count_by_sql_pg_params(sql, &pg_prams).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
         let page_sql = format!(
-            "SELECT * FROM ({}) LIMIT {} OFFSET {}",
-            sql,
-            new_page_size.clone(),
-            (new_page_num - 1) * new_page_size
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
         );
-        let row_vec = self
+        let data = self
             .find_list_by_raw_sql_pg_params(&page_sql, &pg_prams)
             .await?;
-        page_data.data = Some(row_vec);
-        Ok(page_data)
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
     }
-    pub(crate) async fn find_list_by_query(
+    pub(crate) async fn find_list_by_sql_params(
         &mut self,
-        query: &RdbcQueryWrapper,
+        sql: &String,
+        params: &Vec<RdbcValue>,
     ) -> OrmResp<Vec<RdbcOrmRow>> {
-        let (sql, params) = render_query(query, DataBase::Postgres);
         let pg_prams = params
             .iter()
             .map(|v| v as &(dyn ToSql + Sync))
             .collect::<Vec<_>>();
-        self.find_list_by_raw_sql_pg_params(&sql, &pg_prams).await
+        self.find_list_by_raw_sql_pg_params(sql, &pg_prams).await
     }
-    pub(crate) async fn find_one_by_query(
+    pub(crate) async fn find_one_by_sql_params(
         &mut self,
-        query: &RdbcQueryWrapper,
+        sql: &String,
+        params: &Vec<RdbcValue>,
     ) -> OrmResp<Option<RdbcOrmRow>> {
-        let (sql, params) = render_query(query, DataBase::Postgres);
         let pg_prams = params
             .iter()
             .map(|v| v as &(dyn ToSql + Sync))
             .collect::<Vec<_>>();
-        self.find_one_by_raw_sql_pg_params(&sql, &pg_prams).await
+        self.find_one_by_raw_sql_pg_params(sql, &pg_prams).await
     }
-
-    pub(crate) async fn find_count_by_query(&mut self, query: &RdbcQueryWrapper) -> OrmResp<usize> {
-        let (sql, params) = render_query(query, DataBase::Postgres);
-        let pg_prams = params
-            .iter()
-            .map(|v| v as &(dyn ToSql + Sync))
-            .collect::<Vec<_>>();
-        self.find_count_by_sql_pg_params(&sql, &pg_prams).await
-    }
-
     pub(crate) async fn find_list_by_raw_sql_pg_params(
         &mut self,
         sql: &String,
         params: &Vec<&(dyn ToSql + Sync)>,
     ) -> OrmResp<Vec<RdbcOrmRow>> {
-        let rs = self.conn.query(sql.as_str(), &params).await;
-        match rs {
-            Ok(rows) => {
-                let mut list = Vec::new();
-                for row in rows {
-                    let orm_row = RdbcOrmRow::from(row);
-                    list.push(orm_row);
-                }
-                Ok(list)
-            }
-            Err(e) => Err(OrmError {
-                kind: OrmErrorKind::SqlError,
-                msg: e.to_string(),
-            }),
-        }
+        let rows = self.conn.query(sql.as_str(), params).await?;
+        Ok(rows.into_iter().map(RdbcOrmRow::from).collect())
     }
     pub(crate) async fn find_one_by_raw_sql_pg_params(
         &mut self,
         sql: &str,
         params: &Vec<&(dyn ToSql + Sync)>,
     ) -> OrmResp<Option<RdbcOrmRow>> {
-        let count_sql = format!("SELECT COUNT(1) FROM ({}) AS count ", sql);
-        match self.conn.query_opt(count_sql.as_str(), params).await {
-            Ok(row_op) => {
-                if let Some(row) = row_op {
-                    Ok(Some(RdbcOrmRow::from(row)))
-                } else {
-                    Ok(None)
-                }
-            }
-            Err(e) => Err(OrmError {
-                kind: OrmErrorKind::SqlError,
-                msg: e.to_string(),
-            }),
-        }
+        let rows = self.conn.query(sql, params).await?;
+        Ok(rows.into_iter().next().map(RdbcOrmRow::from))
     }
     pub(crate) async fn find_count_by_sql_pg_params(
         &mut self,
         sql: &String,
         params: &Vec<&(dyn ToSql + Sync)>,
     ) -> OrmResp<usize> {
-        if let Some(total_row) = self.find_one_by_raw_sql_pg_params(&sql, &params).await? {
-            if let Some(total_value) = total_row.get_data().get("count") {
-                if let Some(total_value) = total_value.as_number() {
-                    Ok(total_value as usize)
-                } else {
-                    return Err(OrmError {
-                        kind: OrmErrorKind::SqlError,
-                        msg: "查询总数失败: 记录数值解析异常".to_string(),
-                    });
-                }
-            } else {
-                return Err(OrmError {
-                    kind: OrmErrorKind::SqlError,
-                    msg: "查询总数失败: 未获取到记录数".to_string(),
-                });
-            }
-        } else {
-            return Err(OrmError {
-                kind: OrmErrorKind::SqlError,
-                msg: "查询总数失败: 未查询到统计记录".to_string(),
-            });
-        }
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self
+            .find_one_by_raw_sql_pg_params(&count_sql, params)
+            .await?;
+        read_count(total_row)
     }
     pub(crate) async fn execute_insert_by_wrapper(
         &mut self,
@@ -199,6 +167,16 @@ impl<'a> RdbcPostgresConn<'a> {
         self.execute_sql_params(&sql, &params).await
     }
 
+    /// PostgreSQL 的 DDL 支持事务，多条语句在同一事务中执行
+    pub(crate) async fn execute_ddl_statements(&mut self, statements: &Vec<String>) -> OrmResp<()> {
+        let trans = self.conn.transaction().await?;
+        for statement in statements {
+            trans.batch_execute(statement.as_str()).await?;
+        }
+        trans.commit().await?;
+        Ok(())
+    }
+
     pub(crate) async fn execute_sql_params(
         &mut self,
         sql: &String,
@@ -222,10 +200,7 @@ impl<'a> RdbcPostgresTransaction<'a> {
             trans.commit().await?;
             Ok(())
         } else {
-            Err(OrmError {
-                kind: OrmErrorKind::SqlError,
-                msg: "Transaction already completed.".to_string(),
-            })
+            Err(transaction_completed())
         }
     }
 
@@ -234,10 +209,78 @@ impl<'a> RdbcPostgresTransaction<'a> {
             trans.rollback().await?;
             Ok(())
         } else {
-            Err(OrmError {
-                kind: OrmErrorKind::SqlError,
-                msg: "Transaction already completed.".to_string(),
-            })
+            Err(transaction_completed())
         }
     }
+
+    fn trans(&self) -> OrmResp<&Transaction<'a>> {
+        self.trans.as_ref().ok_or_else(transaction_completed)
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let pg_prams = params
+            .iter()
+            .map(|v| v as &(dyn ToSql + Sync))
+            .collect::<Vec<_>>();
+        let rows = self.trans()?.query(sql.as_str(), &pg_prams).await?;
+        Ok(rows.into_iter().map(RdbcOrmRow::from).collect())
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let rows = self.find_list_by_sql_params(sql, params).await?;
+        Ok(rows.into_iter().next())
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let pg_prams = params
+            .iter()
+            .map(|v| v as &(dyn ToSql + Sync))
+            .collect::<Vec<_>>();
+        let row_count = self.trans()?.execute(sql.as_str(), &pg_prams).await?;
+        Ok(row_count as usize)
+    }
+    /// 执行无参数的控制语句，如保存点
+    pub(crate) async fn execute_command(&mut self, sql: &str) -> OrmResp<()> {
+        self.trans()?.batch_execute(sql).await?;
+        Ok(())
+    }
 }
@@ -1,64 +1,189 @@
 use crate::client::pg::conn::RdbcPostgresConn;
-use crate::client::RdbcPostgresTransaction;
-use crate::error::{OrmError, OrmErrorKind, OrmResp};
-use crate::{RdbcConn, RdbcDataSource, RdbcPool, RdbcTransaction};
-use bb8::Pool;
+use crate::client::util::{
+    bb8_builder, bb8_health, bb8_in_use, connect_with_retry, read_tls_file, tls_error,
+    validation_query, LastError,
+};
+use crate::error::{OrmError, OrmResp};
+use crate::{PoolHealth, RdbcConn, RdbcDataSource, RdbcPool, TlsConfig, TlsMode};
+use bb8::{ManageConnection, Pool};
 use bb8_postgres::PostgresConnectionManager;
-use std::str::FromStr;
+use native_tls::{Certificate, Identity, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
 use std::sync::Arc;
-use tokio_postgres::{Config, NoTls};
+use tokio_postgres::config::SslMode;
+use tokio_postgres::{Client, Config, NoTls};
+
+/// 在 bb8_postgres 的基础上使用配置的检测语句检测连接，并剔除已断开的连接
+/// 每次建立连接前按数据源的密码来源读取密码
+pub struct RdbcPostgresConnectionManager {
+    config: Config,
+    tls: Option<MakeTlsConnector>,
+    data_source: Arc<RdbcDataSource>,
+    validation_query: Arc<str>,
+}
+impl RdbcPostgresConnectionManager {
+    pub fn new(
+        config: Config,
+        tls: Option<MakeTlsConnector>,
+        data_source: Arc<RdbcDataSource>,
+        validation_query: Arc<str>,
+    ) -> Self {
+        RdbcPostgresConnectionManager {
+            config,
+            tls,
+            data_source,
+            validation_query,
+        }
+    }
+}
+
+impl ManageConnection for RdbcPostgresConnectionManager {
+    type Connection = Client;
+    type Error = OrmError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        let mut config = self.config.clone();
+        config.password(self.data_source.resolve_password()?);
+        let client = match &self.tls {
+            Some(tls) => {
+                PostgresConnectionManager::new(config, tls.clone())
+                    .connect()
+                    .await?
+            }
+            None => {
+                PostgresConnectionManager::new(config, NoTls)
+                    .connect()
+                    .await?
+            }
+        };
+        Ok(client)
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.simple_query(&self.validation_query).await?;
+        Ok(())
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        conn.is_closed()
+    }
+}
 
 pub struct RdbcPostgresPool {
     datasource: Arc<RdbcDataSource>,
-    pool: Pool<PostgresConnectionManager<NoTls>>,
+    pool: Pool<RdbcPostgresConnectionManager>,
+    validation_query: Arc<str>,
+    last_error: LastError,
 }
 impl RdbcPostgresPool {
-    pub(crate) async fn get_conn(&self) -> OrmResp<RdbcConn> {
+    pub(crate) async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
         let conn_rs = self.pool.get().await;
         match conn_rs {
             Ok(conn) => {
-                let conn = RdbcPostgresConn { conn };
+                let conn = RdbcPostgresConn {
+                    conn,
+                    validation_query: self.validation_query.clone(),
+                };
                 Ok(RdbcConn::Postgres(conn))
             }
-            Err(err) => Err(OrmError {
-                kind: OrmErrorKind::PoolError,
-                msg: err.to_string(),
-            }),
+            Err(err) => {
+                let err = OrmError::from(err);
+                self.last_error.set(&err);
+                Err(err)
+            }
         }
     }
+    pub(crate) fn in_use(&self) -> usize {
+        bb8_in_use(&self.pool.state())
+    }
+    pub(crate) fn health(&self) -> PoolHealth {
+        bb8_health(
+            self.pool.state(),
+            &self.datasource.pool_config,
+            &self.last_error,
+        )
+    }
 }
 
 pub async fn build_postgres_pool(data_source: Arc<RdbcDataSource>) -> OrmResp<RdbcPool> {
-    let conn_str = format!(
-        "host={} port={} user={} password={} dbname={}",
-        data_source.host,
-        data_source.port,
-        data_source.user,
-        data_source.password,
-        data_source.db_name
-    );
+    let pool_config = &data_source.pool_config;
+    pool_config.validate()?;
+    // 逐项设置连接参数，密码等字段包含空格或引号时无需转义
+    let mut cf = Config::new();
+    cf.host(&data_source.host)
+        .port(data_source.port)
+        .user(&data_source.user);
+    if !data_source.db_name.is_empty() {
+        cf.dbname(&data_source.db_name);
+    }
+    if let Some(timeout) = pool_config.connect_timeout {
+        cf.connect_timeout(timeout);
+    }
+    let tls = match data_source.tls.mode {
+        TlsMode::Disable => {
+            cf.ssl_mode(SslMode::Disable);
+            None
+        }
+        TlsMode::Prefer => {
+            cf.ssl_mode(SslMode::Prefer);
+            Some(tls_connector(&data_source.tls)?)
+        }
+        TlsMode::Require | TlsMode::VerifyCa | TlsMode::VerifyFull => {
+            cf.ssl_mode(SslMode::Require);
+            Some(tls_connector(&data_source.tls)?)
+        }
+    };
+    let validation_query = validation_query(&data_source);
+    let last_error = LastError::default();
+    let pool = connect_with_retry(&pool_config.connect_retry, || async {
+        let manage = RdbcPostgresConnectionManager::new(
+            cf.clone(),
+            tls.clone(),
+            data_source.clone(),
+            validation_query.clone(),
+        );
+        bb8_builder(pool_config)
+            .error_sink(Box::new(last_error.clone()))
+            .build(manage)
+            .await
+            .map_err(OrmError::into_conn_error)
+    })
+    .await?;
+    Ok(RdbcPool::Postgres(RdbcPostgresPool {
+        datasource: data_source.clone(),
+        pool,
+        validation_query,
+        last_error,
+    }))
+}
 
-    match Config::from_str(conn_str.as_str()) {
-        Ok(cf) => {
-            let manage = PostgresConnectionManager::new(cf, NoTls);
-            let pool_rs = Pool::builder()
-                .max_size(data_source.pool_config.max_size.clone() as u32)
-                .build(manage)
-                .await;
-            match pool_rs {
-                Ok(pool) => Ok(RdbcPool::Postgres(RdbcPostgresPool {
-                    datasource: data_source.clone(),
-                    pool,
-                })),
-                Err(err) => Err(OrmError {
-                    kind: OrmErrorKind::ConnError,
-                    msg: err.to_string(),
-                }),
-            }
+/// 按 TLS 模式创建连接器，prefer 与 require 只加密不校验证书，与 libpq 一致
+fn tls_connector(tls: &TlsConfig) -> OrmResp<MakeTlsConnector> {
+    let mut builder = TlsConnector::builder();
+    match tls.mode {
+        TlsMode::VerifyFull => {}
+        TlsMode::VerifyCa => {
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        _ => {
+            builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+    }
+    if let Some(ca_cert) = &tls.ca_cert {
+        let pem = read_tls_file(ca_cert)?;
+        builder.add_root_certificate(Certificate::from_pem(&pem).map_err(tls_error)?);
+    }
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => {
+            let cert = read_tls_file(cert)?;
+            let key = read_tls_file(key)?;
+            builder.identity(Identity::from_pkcs8(&cert, &key).map_err(tls_error)?);
         }
-        Err(e) => Err(OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: e.to_string(),
-        }),
+        (None, None) => {}
+        _ => return Err(tls_error("client_cert与client_key需同时配置")),
     }
+    let connector = builder.build().map_err(tls_error)?;
+    Ok(MakeTlsConnector::new(connector))
 }
@@ -0,0 +1,350 @@
+use crate::client::sqlite::pool::RdbcSqliteConnectionManager;
+use crate::client::util::{block_in_place, page_limit_offset, read_count, transaction_completed};
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{IsolationLevel, PageData, RdbcOrmRow, RdbcTransaction, TransactionOptions};
+use bb8::PooledConnection;
+use bmbp_sql::{
+    render_delete, render_insert, render_query, render_update, DataBase, RdbcDeleteWrapper,
+    RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper, RdbcValue,
+};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use std::sync::{Arc, Mutex};
+
+/// rusqlite 连接为同步连接，包装后交给阻塞线程池执行
+pub type SqliteConnection = Arc<Mutex<Connection>>;
+
+pub struct RdbcSqliteConn<'a> {
+    pub conn: PooledConnection<'a, RdbcSqliteConnectionManager>,
+    pub(crate) validation_query: Arc<str>,
+}
+impl<'a> RdbcSqliteConn<'a> {
+    pub(crate) async fn validate(&mut self) -> OrmResp<()> {
+        execute_batch(&self.conn, self.validation_query.to_string()).await
+    }
+    pub async fn get_transaction(
+        &mut self,
+        options: &TransactionOptions,
+    ) -> OrmResp<RdbcTransaction<'_>> {
+        let conn: &SqliteConnection = &self.conn;
+        let begin = match (options.read_only, options.isolation_level) {
+            (true, _) => "PRAGMA query_only = ON; BEGIN",
+            (false, Some(IsolationLevel::Serializable)) => "BEGIN IMMEDIATE",
+            (false, _) => "BEGIN",
+        };
+        if let Err(err) = execute_batch(conn, begin.to_string()).await {
+            if options.read_only {
+                execute_batch(conn, QUERY_ONLY_OFF_SQL.to_string()).await?;
+            }
+            return Err(err);
+        }
+        let trans = RdbcSqliteTransaction {
+            conn: Some(conn),
+            read_only: options.read_only,
+        };
+        Ok(RdbcTransaction::Sqlite(trans))
+    }
+    pub(crate) async fn find_page_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Sqlite);
+        self.find_page_by_sql_params(&sql, &params, page_num, page_size)
+            .await
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Sqlite);
+        self.find_list_by_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn find_one_by_query(
+        &mut self,
+        query: &RdbcQueryWrapper,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let (sql, params) = render_query(query, DataBase::Sqlite);
+        self.find_one_by_sql_params(&sql, &params).await
+    }
+
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        query_rows(&self.conn, sql.clone(), to_sqlite_values(params)).await
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let rows = query_rows(&self.conn, sql.clone(), to_sqlite_values(params)).await?;
+        Ok(rows.into_iter().next())
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_insert_by_wrapper(
+        &mut self,
+        insert: &RdbcInsertWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_insert(insert, DataBase::Sqlite);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_update_by_wrapper(
+        &mut self,
+        update: &RdbcUpdateWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_update(update, DataBase::Sqlite);
+        self.execute_sql_params(&sql, &params).await
+    }
+    pub(crate) async fn execute_delete_by_wrapper(
+        &mut self,
+        delete: &RdbcDeleteWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_delete(delete, DataBase::Sqlite);
+        self.execute_sql_params(&sql, &params).await
+    }
+
+    /// SQLite 的 DDL 支持事务，多条语句在同一事务中执行
+    pub(crate) async fn execute_ddl_statements(&mut self, statements: &Vec<String>) -> OrmResp<()> {
+        let statements = statements.clone();
+        run_blocking(&self.conn, move |conn| {
+            let trans = conn.transaction()?;
+            for statement in statements.iter() {
+                trans.execute_batch(statement.as_str())?;
+            }
+            trans.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        execute_rows(&self.conn, sql.clone(), to_sqlite_values(params)).await
+    }
+}
+
+/// 只读事务结束后恢复连接的写权限
+const QUERY_ONLY_OFF_SQL: &str = "PRAGMA query_only = OFF";
+
+pub struct RdbcSqliteTransaction<'a> {
+    pub conn: Option<&'a SqliteConnection>,
+    read_only: bool,
+}
+impl<'a> RdbcSqliteTransaction<'a> {
+    pub async fn commit(&mut self) -> OrmResp<()> {
+        if let Some(conn) = self.conn.take() {
+            let rs = execute_batch(conn, "COMMIT".to_string()).await;
+            if self.read_only {
+                execute_batch(conn, QUERY_ONLY_OFF_SQL.to_string()).await?;
+            }
+            rs
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    pub async fn rollback(&mut self) -> OrmResp<()> {
+        if let Some(conn) = self.conn.take() {
+            let rs = execute_batch(conn, "ROLLBACK".to_string()).await;
+            if self.read_only {
+                execute_batch(conn, QUERY_ONLY_OFF_SQL.to_string()).await?;
+            }
+            rs
+        } else {
+            Err(transaction_completed())
+        }
+    }
+
+    fn conn(&self) -> OrmResp<&'a SqliteConnection> {
+        self.conn.ok_or_else(transaction_completed)
+    }
+    pub(crate) async fn find_page_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        let total = self.find_count_by_sql_params(sql, params).await?;
+        let (limit, offset) = page_limit_offset(page_num, page_size);
+        let page_sql = format!(
+            "SELECT * FROM ({}) AS page_tmp LIMIT {} OFFSET {}",
+            sql, limit, offset
+        );
+        let data = self.find_list_by_sql_params(&page_sql, params).await?;
+        Ok(PageData {
+            page_num,
+            page_size,
+            total,
+            data: Some(data),
+        })
+    }
+    pub(crate) async fn find_list_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        query_rows(self.conn()?, sql.clone(), to_sqlite_values(params)).await
+    }
+    pub(crate) async fn find_one_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        let rows = self.find_list_by_sql_params(sql, params).await?;
+        Ok(rows.into_iter().next())
+    }
+    pub(crate) async fn find_count_by_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        let count_sql = format!("SELECT COUNT(1) AS count FROM ({}) AS count_tmp", sql);
+        let total_row = self.find_one_by_sql_params(&count_sql, params).await?;
+        read_count(total_row)
+    }
+    pub(crate) async fn execute_sql_params(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        execute_rows(self.conn()?, sql.clone(), to_sqlite_values(params)).await
+    }
+    /// 执行无参数的控制语句，如保存点
+    pub(crate) async fn execute_command(&mut self, sql: &str) -> OrmResp<()> {
+        execute_batch(self.conn()?, sql.to_string()).await
+    }
+}
+
+impl<'a> Drop for RdbcSqliteTransaction<'a> {
+    /// 未提交的事务在释放时回滚，避免连接归还连接池后仍处于事务中
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let read_only = self.read_only;
+            block_in_place(|| {
+                if let Ok(conn) = conn.lock() {
+                    if let Err(err) = conn.execute_batch("ROLLBACK") {
+                        tracing::warn!("sqlite事务回滚失败: {}", err);
+                    }
+                    if read_only {
+                        if let Err(err) = conn.execute_batch(QUERY_ONLY_OFF_SQL) {
+                            tracing::warn!("sqlite恢复连接写权限失败: {}", err);
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// 在阻塞线程池中执行 rusqlite 操作
+pub(crate) async fn run_blocking<F, R>(conn: &SqliteConnection, f: F) -> OrmResp<R>
+where
+    F: FnOnce(&mut Connection) -> OrmResp<R> + Send + 'static,
+    R: Send + 'static,
+{
+    let conn = conn.clone();
+    let join_rs = tokio::task::spawn_blocking(move || match conn.lock() {
+        Ok(mut guard) => f(&mut guard),
+        Err(err) => Err(OrmError::new(OrmErrorKind::ConnError, err.to_string())),
+    })
+    .await;
+    match join_rs {
+        Ok(rs) => rs,
+        Err(err) => Err(OrmError::new(OrmErrorKind::ConnError, err.to_string())),
+    }
+}
+
+pub(crate) async fn query_rows(
+    conn: &SqliteConnection,
+    sql: String,
+    params: Vec<Value>,
+) -> OrmResp<Vec<RdbcOrmRow>> {
+    run_blocking(conn, move |conn| {
+        let mut stmt = conn.prepare(sql.as_str())?;
+        let mut rows = stmt.query(params_from_iter(params.iter()))?;
+        let mut list = Vec::new();
+        while let Some(row) = rows.next()? {
+            list.push(RdbcOrmRow::from(row));
+        }
+        Ok(list)
+    })
+    .await
+}
+
+pub(crate) async fn execute_rows(
+    conn: &SqliteConnection,
+    sql: String,
+    params: Vec<Value>,
+) -> OrmResp<usize> {
+    run_blocking(conn, move |conn| {
+        let row_count = conn.execute(sql.as_str(), params_from_iter(params.iter()))?;
+        Ok(row_count)
+    })
+    .await
+}
+
+pub(crate) async fn execute_batch(conn: &SqliteConnection, sql: String) -> OrmResp<()> {
+    run_blocking(conn, move |conn| {
+        conn.execute_batch(sql.as_str())?;
+        Ok(())
+    })
+    .await
+}
+
+/// RdbcValue 转换为 sqlite 存储类型
+pub(crate) fn to_sqlite_values(params: &Vec<RdbcValue>) -> Vec<Value> {
+    params.iter().map(to_sqlite_value).collect()
+}
+
+fn to_sqlite_value(value: &RdbcValue) -> Value {
+    match value {
+        RdbcValue::Varchar(v) => Value::Text(v.clone()),
+        RdbcValue::Int(v) => Value::Integer(*v as i64),
+        RdbcValue::BigInt(v) => Value::Integer(*v),
+        RdbcValue::Double(v) => Value::Real(*v as f64),
+        RdbcValue::Boolean(v) => Value::Integer(if *v { 1 } else { 0 }),
+        RdbcValue::DateTime(v) => Value::Text(v.format("%Y-%m-%d %H:%M:%S%.f").to_string()),
+        RdbcValue::Null => Value::Null,
+        _ => {
+            tracing::warn!("sqlite数据库暂未支持的参数类型: {:#?}", value);
+            Value::Null
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::client::sqlite::conn::{run_blocking, RdbcSqliteConn, SqliteConnection};
+use crate::client::util::{
+    bb8_builder, bb8_health, bb8_in_use, connect_with_retry, validation_query, LastError,
+};
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{PoolHealth, RdbcConn, RdbcDataSource, RdbcPool};
+use bb8::{ManageConnection, Pool};
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 内存数据库标识，db_name 为空时同样视为内存数据库
+pub const SQLITE_MEMORY: &str = ":memory:";
+
+/// 内存数据库序号，每个连接池使用独立的共享缓存内存数据库
+static MEMORY_SEQ: AtomicUsize = AtomicUsize::new(1);
+
+pub struct RdbcSqliteConnectionManager {
+    path: String,
+    memory: bool,
+    validation_query: Arc<str>,
+    /// 共享缓存的内存数据库在最后一个连接关闭时销毁，保留一个不借出的连接维持数据
+    keeper: Arc<Mutex<Option<Connection>>>,
+}
+impl RdbcSqliteConnectionManager {
+    pub fn new(path: String) -> Self {
+        let memory = path.is_empty() || path == SQLITE_MEMORY;
+        let path = if memory {
+            let seq = MEMORY_SEQ.fetch_add(1, Ordering::Relaxed);
+            format!("file:bmbp_memory_{}?mode=memory&cache=shared", seq)
+        } else {
+            path
+        };
+        RdbcSqliteConnectionManager {
+            path,
+            memory,
+            validation_query: Arc::from("SELECT 1"),
+            keeper: Arc::new(Mutex::new(None)),
+        }
+    }
+    /// 设置连接检测语句
+    pub fn validation_query(mut self, validation_query: Arc<str>) -> Self {
+        self.validation_query = validation_query;
+        self
+    }
+    pub fn is_memory(&self) -> bool {
+        self.memory
+    }
+}
+
+impl ManageConnection for RdbcSqliteConnectionManager {
+    type Connection = SqliteConnection;
+    type Error = OrmError;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        let path = self.path.clone();
+        let keeper = self.memory.then(|| self.keeper.clone());
+        let join_rs = tokio::task::spawn_blocking(move || -> OrmResp<Connection> {
+            let conn = Connection::open(&path)?;
+            if let Some(keeper) = keeper {
+                if let Ok(mut keeper) = keeper.lock() {
+                    if keeper.is_none() {
+                        *keeper = Some(Connection::open(&path)?);
+                    }
+                }
+            }
+            Ok(conn)
+        })
+        .await;
+        match join_rs {
+            Ok(conn) => Ok(Arc::new(Mutex::new(conn?))),
+            Err(err) => Err(OrmError::new(OrmErrorKind::ConnError, err.to_string())),
+        }
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        let validation_query = self.validation_query.clone();
+        run_blocking(conn, move |conn| {
+            conn.execute_batch(&validation_query)?;
+            Ok(())
+        })
+        .await
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        conn.is_poisoned()
+    }
+}
+
+pub struct RdbcSqlitePool {
+    datasource: Arc<RdbcDataSource>,
+    pool: Pool<RdbcSqliteConnectionManager>,
+    validation_query: Arc<str>,
+    last_error: LastError,
+}
+impl RdbcSqlitePool {
+    pub(crate) async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
+        let conn_rs = self.pool.get().await;
+        match conn_rs {
+            Ok(conn) => {
+                let conn = RdbcSqliteConn {
+                    conn,
+                    validation_query: self.validation_query.clone(),
+                };
+                Ok(RdbcConn::Sqlite(conn))
+            }
+            Err(err) => {
+                let err = OrmError::from(err);
+                self.last_error.set(&err);
+                Err(err)
+            }
+        }
+    }
+    pub(crate) fn in_use(&self) -> usize {
+        bb8_in_use(&self.pool.state())
+    }
+    pub(crate) fn health(&self) -> PoolHealth {
+        bb8_health(
+            self.pool.state(),
+            &self.datasource.pool_config,
+            &self.last_error,
+        )
+    }
+}
+
+/// 构建 sqlite 连接池，db_name 为数据库文件路径
+/// 内存数据库使用共享缓存，同一连接池的连接访问同一个数据库，不同连接池的数据库相互独立；
+/// 共享缓存按表加锁，一个连接写入未提交的表被其它连接读写时立即返回 SQLITE_LOCKED 错误
+pub async fn build_sqlite_pool(data_source: Arc<RdbcDataSource>) -> OrmResp<RdbcPool> {
+    let pool_config = &data_source.pool_config;
+    pool_config.validate()?;
+    let validation_query = validation_query(&data_source);
+    let last_error = LastError::default();
+    let pool = connect_with_retry(&pool_config.connect_retry, || async {
+        let manage = RdbcSqliteConnectionManager::new(data_source.db_name.clone())
+            .validation_query(validation_query.clone());
+        bb8_builder(pool_config)
+            .error_sink(Box::new(last_error.clone()))
+            .build(manage)
+            .await
+    })
+    .await?;
+    Ok(RdbcPool::Sqlite(RdbcSqlitePool {
+        datasource: data_source.clone(),
+        pool,
+        validation_query,
+        last_error,
+    }))
+}
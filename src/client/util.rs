@@ -0,0 +1,176 @@
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{ConnectRetry, PoolConfig, PoolHealth, RdbcDataSource, RdbcDbType, RdbcOrmRow};
+use bb8::{Builder, ErrorSink, ManageConnection, Pool, State};
+use std::fmt::Display;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// 统计查询结果转换为记录数
+pub(crate) fn read_count(total_row: Option<RdbcOrmRow>) -> OrmResp<usize> {
+    if let Some(total_row) = total_row {
+        if let Some(total_value) = total_row.get_data().get("count") {
+            if let Some(total_value) = total_value.as_number() {
+                Ok(total_value as usize)
+            } else {
+                Err(OrmError::new(
+                    OrmErrorKind::SqlError,
+                    "查询总数失败: 记录数值解析异常".to_string(),
+                ))
+            }
+        } else {
+            Err(OrmError::new(
+                OrmErrorKind::SqlError,
+                "查询总数失败: 未获取到记录数".to_string(),
+            ))
+        }
+    } else {
+        Err(OrmError::new(
+            OrmErrorKind::SqlError,
+            "查询总数失败: 未查询到统计记录".to_string(),
+        ))
+    }
+}
+
+/// 非事务性 DDL 执行失败时说明已生效的语句，便于人工处理
+pub(crate) fn ddl_partial_error(index: usize, total: usize, mut err: OrmError) -> OrmError {
+    err.msg = format!(
+        "第{}/{}条DDL执行失败，之前的{}条语句已生效且无法回滚: {}",
+        index + 1,
+        total,
+        index,
+        err.msg
+    );
+    err
+}
+
+/// 分页参数转换为 (limit, offset)，页码从1开始，页大小默认10
+pub(crate) fn page_limit_offset(page_num: usize, page_size: usize) -> (usize, usize) {
+    let page_num = if page_num > 1 { page_num } else { 1 };
+    let page_size = if page_size > 0 { page_size } else { 10 };
+    (page_size, (page_num - 1) * page_size)
+}
+
+pub(crate) fn transaction_completed() -> OrmError {
+    OrmError::new(
+        OrmErrorKind::SqlError,
+        "Transaction already completed.".to_string(),
+    )
+}
+
+/// 在异步任务中执行同步的数据库操作，多线程运行时下通过 block_in_place 避免阻塞其它任务
+pub(crate) fn block_in_place<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// 按连接池配置创建 bb8 连接池构建器
+pub(crate) fn bb8_builder<M: ManageConnection>(config: &PoolConfig) -> Builder<M> {
+    let mut builder = Pool::builder()
+        .max_size(config.max_size as u32)
+        .min_idle(Some(config.min_size as u32))
+        .idle_timeout(config.idle_timeout)
+        .max_lifetime(config.max_lifetime)
+        .test_on_check_out(config.test_on_checkout);
+    if let Some(timeout) = config.acquire_timeout {
+        builder = builder.connection_timeout(timeout);
+    }
+    builder
+}
+
+/// 首次连接失败时按重试策略间隔重试
+pub(crate) async fn connect_with_retry<F, Fut, T>(
+    retry: &ConnectRetry,
+    mut connect: F,
+) -> OrmResp<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = OrmResp<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match connect().await {
+            Err(err) if attempt < retry.max_attempts => {
+                tracing::warn!(
+                    "数据库第{}次连接失败，{}ms后重试: {}",
+                    attempt,
+                    retry.interval.as_millis(),
+                    err
+                );
+                tokio::time::sleep(retry.interval).await;
+                attempt += 1;
+            }
+            rs => return rs,
+        }
+    }
+}
+
+/// 连接检测语句
+pub(crate) fn validation_query(data_source: &RdbcDataSource) -> Arc<str> {
+    match &data_source.pool_config.validation_query {
+        Some(query) if !query.trim().is_empty() => Arc::from(query.as_str()),
+        _ => match data_source.db_type {
+            RdbcDbType::Oracle => Arc::from("SELECT 1 FROM DUAL"),
+            _ => Arc::from("SELECT 1"),
+        },
+    }
+}
+
+/// 连接池最近一次错误，同时作为 bb8 的错误接收器记录后台建立连接的错误
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LastError(Arc<Mutex<Option<String>>>);
+
+impl LastError {
+    pub(crate) fn set(&self, err: &dyn Display) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Some(err.to_string());
+        }
+    }
+    pub(crate) fn get(&self) -> Option<String> {
+        self.0.lock().ok().and_then(|last| last.clone())
+    }
+}
+
+impl<E: Display + Send + 'static> ErrorSink<E> for LastError {
+    fn sink(&self, error: E) {
+        tracing::warn!("数据库连接失败: {}", error);
+        self.set(&error);
+    }
+    fn boxed_clone(&self) -> Box<dyn ErrorSink<E>> {
+        Box::new(self.clone())
+    }
+}
+
+/// bb8 连接池借出的连接数
+pub(crate) fn bb8_in_use(state: &State) -> usize {
+    state.connections.saturating_sub(state.idle_connections) as usize
+}
+
+/// bb8 连接池状态
+pub(crate) fn bb8_health(state: State, config: &PoolConfig, last_error: &LastError) -> PoolHealth {
+    PoolHealth {
+        healthy: true,
+        max_size: config.max_size,
+        total: Some(state.connections as usize),
+        idle: Some(state.idle_connections as usize),
+        in_use: bb8_in_use(&state),
+        last_error: last_error.get(),
+    }
+}
+
+/// 读取 TLS 证书或私钥文件
+pub(crate) fn read_tls_file(path: &Path) -> OrmResp<Vec<u8>> {
+    std::fs::read(path).map_err(|err| tls_error(format!("读取{}失败: {}", path.display(), err)))
+}
+
+pub(crate) fn tls_error(err: impl Display) -> OrmError {
+    OrmError::new(OrmErrorKind::ConnError, format!("TLS配置错误: {}", err))
+}
@@ -0,0 +1,412 @@
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{PoolConfig, RdbcDataSource};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 环境变量默认前缀
+pub const DEFAULT_ENV_PREFIX: &str = "BMBP_DB_";
+
+/// 按数值或布尔值解析的环境变量，其余环境变量均按字符串处理，避免纯数字密码被解析为数值
+const ENV_TYPED_KEYS: [&str; 5] = [
+    "port",
+    "pool_config.max_size",
+    "pool_config.min_size",
+    "pool_config.connect_retry.max_attempts",
+    "pool_config.test_on_checkout",
+];
+
+enum ConfigSource {
+    File { path: PathBuf, required: bool },
+    Env(String),
+}
+
+/// 数据源配置加载器，按添加顺序逐层加载，后加载的配置项覆盖先加载的同名配置项
+/// ```ignore
+/// let ds = RdbcConfigLoader::new()
+///     .file("config/db.toml")
+///     .optional_file("config/db.local.yaml")
+///     .env("BMBP_DB_")
+///     .load_data_source()?;
+/// ```
+/// 配置文件按扩展名识别 toml、yaml/yml、json 格式，结构与 RdbcDataSource 一致；
+/// 环境变量去掉前缀后转为小写作为配置项，POOL_、POOL_CONNECT_RETRY_、TLS_ 开头的分别对应
+/// pool_config、pool_config.connect_retry、tls 下的配置项，如 BMBP_DB_POOL_MAX_SIZE、BMBP_DB_TLS_MODE；
+/// 时长配置项可使用数值秒数或 "500ms"、"30s"、"5m"、"1h"、"1m30s" 等写法
+#[derive(Default)]
+pub struct RdbcConfigLoader {
+    sources: Vec<ConfigSource>,
+}
+
+impl RdbcConfigLoader {
+    pub fn new() -> Self {
+        RdbcConfigLoader::default()
+    }
+    /// 加载配置文件，文件不存在时报错
+    pub fn file(mut self, path: impl AsRef<Path>) -> Self {
+        self.sources.push(ConfigSource::File {
+            path: path.as_ref().to_path_buf(),
+            required: true,
+        });
+        self
+    }
+    /// 加载配置文件，文件不存在时跳过
+    pub fn optional_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.sources.push(ConfigSource::File {
+            path: path.as_ref().to_path_buf(),
+            required: false,
+        });
+        self
+    }
+    /// 加载指定前缀的环境变量
+    pub fn env(mut self, prefix: &str) -> Self {
+        self.sources.push(ConfigSource::Env(prefix.to_string()));
+        self
+    }
+
+    /// 加载并校验数据源配置
+    pub fn load_data_source(&self) -> OrmResp<RdbcDataSource> {
+        let data_source: RdbcDataSource = from_value(self.load_value()?)?;
+        data_source.validate()?;
+        Ok(data_source)
+    }
+    /// 加载并校验连接池配置，配置项位于 pool_config 下，环境变量同样使用 POOL_ 前缀
+    pub fn load_pool_config(&self) -> OrmResp<PoolConfig> {
+        let pool_config = match self.load_value()? {
+            Value::Object(mut map) => map.remove("pool_config").unwrap_or(Value::Null),
+            _ => Value::Null,
+        };
+        let pool_config: PoolConfig = match pool_config {
+            Value::Null => PoolConfig::default(),
+            value => from_value(value)?,
+        };
+        pool_config.validate()?;
+        Ok(pool_config)
+    }
+
+    fn load_value(&self) -> OrmResp<Value> {
+        let mut merged = Value::Object(Map::new());
+        for source in &self.sources {
+            let layer = match source {
+                ConfigSource::File { path, required } => match read_file(path, *required)? {
+                    Some(layer) => layer,
+                    None => continue,
+                },
+                ConfigSource::Env(prefix) => read_env(prefix),
+            };
+            merge_value(&mut merged, layer);
+        }
+        Ok(merged)
+    }
+}
+
+impl RdbcDataSource {
+    /// 从配置文件加载数据源
+    pub fn from_file(path: impl AsRef<Path>) -> OrmResp<Self> {
+        RdbcConfigLoader::new().file(path).load_data_source()
+    }
+    /// 从指定前缀的环境变量加载数据源
+    pub fn from_env(prefix: &str) -> OrmResp<Self> {
+        RdbcConfigLoader::new().env(prefix).load_data_source()
+    }
+}
+
+impl PoolConfig {
+    /// 从配置文件的 pool_config 下加载连接池配置
+    pub fn from_file(path: impl AsRef<Path>) -> OrmResp<Self> {
+        RdbcConfigLoader::new().file(path).load_pool_config()
+    }
+    /// 从指定前缀的 POOL_ 环境变量加载连接池配置
+    pub fn from_env(prefix: &str) -> OrmResp<Self> {
+        RdbcConfigLoader::new().env(prefix).load_pool_config()
+    }
+}
+
+fn read_file(path: &Path, required: bool) -> OrmResp<Option<Value>> {
+    if !required && !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| config_error(format!("读取配置文件{}失败: {}", path.display(), err)))?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let value = match extension.as_str() {
+        "toml" => toml::from_str::<Value>(&content).map_err(|err| err.to_string()),
+        "yaml" | "yml" => serde_yaml::from_str::<Value>(&content).map_err(|err| err.to_string()),
+        "json" => serde_json::from_str::<Value>(&content).map_err(|err| err.to_string()),
+        _ => {
+            return Err(config_error(format!(
+                "不支持的配置文件格式: {}",
+                path.display()
+            )))
+        }
+    };
+    match value {
+        Ok(Value::Null) => Ok(Some(Value::Object(Map::new()))),
+        Ok(value) => Ok(Some(value)),
+        Err(err) => Err(config_error(format!(
+            "解析配置文件{}失败: {}",
+            path.display(),
+            err
+        ))),
+    }
+}
+
+fn read_env(prefix: &str) -> Value {
+    let mut layer = Map::new();
+    for (name, value) in std::env::vars() {
+        let Some(key) = name.strip_prefix(prefix) else {
+            continue;
+        };
+        let key = key.to_ascii_lowercase();
+        let path = if let Some(key) = key.strip_prefix("pool_connect_retry_") {
+            vec!["pool_config", "connect_retry", key]
+        } else if let Some(key) = key.strip_prefix("pool_") {
+            vec!["pool_config", key]
+        } else if let Some(key) = key.strip_prefix("tls_") {
+            vec!["tls", key]
+        } else {
+            vec![key.as_str()]
+        };
+        let value = if ENV_TYPED_KEYS.contains(&path.join(".").as_str()) {
+            serde_json::from_str(&value).unwrap_or(Value::String(value))
+        } else {
+            Value::String(value)
+        };
+        insert_path(&mut layer, &path, value);
+    }
+    Value::Object(layer)
+}
+
+fn insert_path(map: &mut Map<String, Value>, path: &[&str], value: Value) {
+    match path {
+        [key] => {
+            map.insert(key.to_string(), value);
+        }
+        [key, rest @ ..] => {
+            let child = map
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(child) = child {
+                insert_path(child, rest, value);
+            }
+        }
+        [] => {}
+    }
+}
+
+/// 逐层合并配置，对象按键合并，其余值整体覆盖
+fn merge_value(target: &mut Value, layer: Value) {
+    match (target, layer) {
+        (Value::Object(target), Value::Object(layer)) => {
+            for (key, value) in layer {
+                match target.get_mut(&key) {
+                    Some(current) => merge_value(current, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, layer) => *target = layer,
+    }
+}
+
+pub(crate) fn from_value<T: DeserializeOwned>(value: Value) -> OrmResp<T> {
+    serde_json::from_value(value).map_err(|err| config_error(format!("配置项错误: {}", err)))
+}
+
+pub(crate) fn config_error(msg: String) -> OrmError {
+    OrmError::new(OrmErrorKind::ConfigError, msg)
+}
+
+/// 解析时长，无单位时按秒计算，支持 ms、s、m、h、d 及组合写法如 1m30s
+pub(crate) fn parse_duration(value: &str) -> OrmResp<Duration> {
+    let text = value.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let invalid = || config_error(format!("无效的时长: {}", value));
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let number: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let duration = match rest[..unit_len].trim() {
+            "ms" => Duration::from_millis(number),
+            "s" => Duration::from_secs(number),
+            "m" => Duration::from_secs(number.saturating_mul(60)),
+            "h" => Duration::from_secs(number.saturating_mul(3600)),
+            "d" => Duration::from_secs(number.saturating_mul(86400)),
+            _ => return Err(invalid()),
+        };
+        total = total.saturating_add(duration);
+        rest = rest[unit_len..].trim_start();
+    }
+    Ok(total)
+}
+
+/// 时长配置的几种写法，兼容 Duration 默认的 {secs, nanos} 结构
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DurationValue {
+    Secs(u64),
+    Text(String),
+    Struct(Duration),
+}
+
+impl DurationValue {
+    fn into_duration<E: serde::de::Error>(self) -> Result<Duration, E> {
+        match self {
+            DurationValue::Secs(secs) => Ok(Duration::from_secs(secs)),
+            DurationValue::Text(text) => parse_duration(&text).map_err(|err| E::custom(err.msg)),
+            DurationValue::Struct(duration) => Ok(duration),
+        }
+    }
+}
+
+pub(crate) fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    DurationValue::deserialize(deserializer)?.into_duration()
+}
+
+pub(crate) fn deserialize_option_duration<'de, D>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<DurationValue>::deserialize(deserializer)? {
+        Some(value) => value.into_duration().map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RdbcDbType, TlsMode};
+    use serde_json::json;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+        assert_eq!(
+            parse_duration(" 500ms ").unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(parse_duration("1m30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("1h 5m").unwrap(), Duration::from_secs(3900));
+        assert_eq!(parse_duration("2d").unwrap(), Duration::from_secs(172_800));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid() {
+        for value in ["", "  ", "m", "1x", "1.5s", "-1s", "s30", "1m30"] {
+            assert!(parse_duration(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn merge_value_overrides_by_key() {
+        let mut target = json!({
+            "host": "a",
+            "pool_config": {"max_size": 10, "min_size": 1},
+            "tls": {"mode": "disable"}
+        });
+        merge_value(
+            &mut target,
+            json!({"host": "b", "pool_config": {"max_size": 20}, "tls": null}),
+        );
+        assert_eq!(
+            target,
+            json!({
+                "host": "b",
+                "pool_config": {"max_size": 20, "min_size": 1},
+                "tls": null
+            })
+        );
+    }
+
+    #[test]
+    fn read_env_maps_nested_keys() {
+        let prefix = "BMBP_ORM_TEST_READ_ENV_";
+        std::env::set_var(format!("{}PORT", prefix), "5433");
+        std::env::set_var(format!("{}PASSWORD", prefix), "123456");
+        std::env::set_var(format!("{}POOL_MAX_SIZE", prefix), "8");
+        std::env::set_var(format!("{}POOL_IDLE_TIMEOUT", prefix), "1m30s");
+        std::env::set_var(format!("{}POOL_CONNECT_RETRY_MAX_ATTEMPTS", prefix), "3");
+        std::env::set_var(format!("{}TLS_MODE", prefix), "require");
+        assert_eq!(
+            read_env(prefix),
+            json!({
+                "port": 5433,
+                "password": "123456",
+                "pool_config": {
+                    "max_size": 8,
+                    "idle_timeout": "1m30s",
+                    "connect_retry": {"max_attempts": 3}
+                },
+                "tls": {"mode": "require"}
+            })
+        );
+    }
+
+    #[test]
+    fn env_overrides_file() {
+        let prefix = "BMBP_ORM_TEST_LAYER_";
+        let path = std::env::temp_dir().join(format!("bmbp_orm_layer_{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "db_type = \"Postgres\"\nhost = \"file-host\"\nport = 5432\nuser = \"app\"\n\
+             [pool_config]\nmax_size = 5\nacquire_timeout = \"10s\"\n",
+        )
+        .unwrap();
+        std::env::set_var(format!("{}HOST", prefix), "env-host");
+        std::env::set_var(format!("{}POOL_MAX_SIZE", prefix), "12");
+        std::env::set_var(format!("{}TLS_MODE", prefix), "verify-full");
+        let ds = RdbcConfigLoader::new()
+            .file(&path)
+            .optional_file(path.with_extension("missing.yaml"))
+            .env(prefix)
+            .load_data_source();
+        std::fs::remove_file(&path).unwrap();
+        let ds = ds.unwrap();
+        assert_eq!(ds.db_type, RdbcDbType::Postgres);
+        assert_eq!(ds.host, "env-host");
+        assert_eq!(ds.port, 5432);
+        assert_eq!(ds.user, "app");
+        assert_eq!(ds.pool_config.max_size, 12);
+        assert_eq!(
+            ds.pool_config.acquire_timeout,
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(ds.pool_config.min_size, PoolConfig::default().min_size);
+        assert_eq!(ds.tls.mode, TlsMode::VerifyFull);
+    }
+
+    #[test]
+    fn missing_required_file_fails() {
+        let path = std::env::temp_dir().join("bmbp_orm_missing_config.toml");
+        assert!(RdbcConfigLoader::new()
+            .file(path)
+            .load_data_source()
+            .is_err());
+    }
+}
@@ -1,22 +1,39 @@
 use crate::bean::RdbcOrmRow;
-use crate::client::{RdbcPostgresConn, RdbcPostgresTransaction};
-use crate::error::OrmResp;
-use crate::PageData;
-use bmbp_sql::{RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper};
+use crate::client::{RdbcMysqlConn, RdbcOracleConn, RdbcPostgresConn, RdbcSqliteConn};
+use crate::ds::RdbcDbType;
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::script::split_sql_statements;
+use crate::{PageData, RdbcTransaction, TransactionOptions};
+use bmbp_sql::{
+    render_ddl, DataBase, RdbcDdlWrapper, RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper,
+    RdbcUpdateWrapper, RdbcValue,
+};
 
 pub enum RdbcConn<'a> {
     Postgres(RdbcPostgresConn<'a>),
+    Mysql(RdbcMysqlConn<'a>),
+    Sqlite(RdbcSqliteConn<'a>),
+    Oracle(RdbcOracleConn<'a>),
 }
 
 impl<'a> RdbcConn<'a> {
     pub async fn validate(&mut self) -> OrmResp<()> {
         match self {
             RdbcConn::Postgres(c) => c.validate().await,
+            RdbcConn::Mysql(c) => c.validate().await,
+            RdbcConn::Sqlite(c) => c.validate().await,
+            RdbcConn::Oracle(c) => c.validate().await,
         }
     }
-    pub async fn get_transaction(&mut self) -> OrmResp<RdbcTransaction> {
+    pub async fn get_transaction(
+        &mut self,
+        options: &TransactionOptions,
+    ) -> OrmResp<RdbcTransaction<'_>> {
         match self {
-            RdbcConn::Postgres(c) => c.get_transaction().await,
+            RdbcConn::Postgres(c) => c.get_transaction(options).await,
+            RdbcConn::Mysql(c) => c.get_transaction(options).await,
+            RdbcConn::Sqlite(c) => c.get_transaction(options).await,
+            RdbcConn::Oracle(c) => c.get_transaction(options).await,
         }
     }
     pub(crate) async fn find_list_by_query(
@@ -25,6 +42,9 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<Vec<RdbcOrmRow>> {
         match self {
             RdbcConn::Postgres(c) => c.find_list_by_query(query).await,
+            RdbcConn::Mysql(c) => c.find_list_by_query(query).await,
+            RdbcConn::Sqlite(c) => c.find_list_by_query(query).await,
+            RdbcConn::Oracle(c) => c.find_list_by_query(query).await,
         }
     }
     pub(crate) async fn find_page_by_query(
@@ -35,6 +55,9 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<PageData<RdbcOrmRow>> {
         match self {
             RdbcConn::Postgres(c) => c.find_page_by_query(query, page_num, page_size).await,
+            RdbcConn::Mysql(c) => c.find_page_by_query(query, page_num, page_size).await,
+            RdbcConn::Sqlite(c) => c.find_page_by_query(query, page_num, page_size).await,
+            RdbcConn::Oracle(c) => c.find_page_by_query(query, page_num, page_size).await,
         }
     }
     pub(crate) async fn find_one_by_query(
@@ -43,6 +66,9 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<Option<RdbcOrmRow>> {
         match self {
             RdbcConn::Postgres(c) => c.find_one_by_query(query).await,
+            RdbcConn::Mysql(c) => c.find_one_by_query(query).await,
+            RdbcConn::Sqlite(c) => c.find_one_by_query(query).await,
+            RdbcConn::Oracle(c) => c.find_one_by_query(query).await,
         }
     }
     pub(crate) async fn execute_insert_by_wrapper(
@@ -51,6 +77,9 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<usize> {
         match self {
             RdbcConn::Postgres(c) => c.execute_insert_by_wrapper(insert).await,
+            RdbcConn::Mysql(c) => c.execute_insert_by_wrapper(insert).await,
+            RdbcConn::Sqlite(c) => c.execute_insert_by_wrapper(insert).await,
+            RdbcConn::Oracle(c) => c.execute_insert_by_wrapper(insert).await,
         }
     }
     pub(crate) async fn execute_update_by_wrapper(
@@ -59,6 +88,9 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<usize> {
         match self {
             RdbcConn::Postgres(c) => c.execute_update_by_wrapper(update).await,
+            RdbcConn::Mysql(c) => c.execute_update_by_wrapper(update).await,
+            RdbcConn::Sqlite(c) => c.execute_update_by_wrapper(update).await,
+            RdbcConn::Oracle(c) => c.execute_update_by_wrapper(update).await,
         }
     }
     pub(crate) async fn execute_delete_by_wrapper(
@@ -67,17 +99,122 @@ impl<'a> RdbcConn<'a> {
     ) -> OrmResp<usize> {
         match self {
             RdbcConn::Postgres(c) => c.execute_delete_by_wrapper(delete).await,
+            RdbcConn::Mysql(c) => c.execute_delete_by_wrapper(delete).await,
+            RdbcConn::Sqlite(c) => c.execute_delete_by_wrapper(delete).await,
+            RdbcConn::Oracle(c) => c.execute_delete_by_wrapper(delete).await,
         }
     }
-}
-pub enum RdbcTransaction<'a> {
-    Postgres(RdbcPostgresTransaction<'a>),
-}
-
-impl<'a> RdbcTransaction<'a> {
-    pub async fn commit(&mut self) -> OrmResp<()> {
+    pub(crate) async fn find_page_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        match self {
+            RdbcConn::Postgres(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcConn::Mysql(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcConn::Sqlite(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcConn::Oracle(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+        }
+    }
+    pub(crate) async fn find_list_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        match self {
+            RdbcConn::Postgres(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcConn::Mysql(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcConn::Sqlite(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcConn::Oracle(c) => c.find_list_by_sql_params(sql, params).await,
+        }
+    }
+    pub(crate) async fn find_one_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        match self {
+            RdbcConn::Postgres(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcConn::Mysql(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcConn::Sqlite(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcConn::Oracle(c) => c.find_one_by_sql_params(sql, params).await,
+        }
+    }
+    pub(crate) async fn execute_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        match self {
+            RdbcConn::Postgres(c) => c.execute_sql_params(sql, params).await,
+            RdbcConn::Mysql(c) => c.execute_sql_params(sql, params).await,
+            RdbcConn::Sqlite(c) => c.execute_sql_params(sql, params).await,
+            RdbcConn::Oracle(c) => c.execute_sql_params(sql, params).await,
+        }
+    }
+    /// 连接对应的 SQL 方言
+    pub(crate) fn database(&self) -> DataBase {
+        match self {
+            RdbcConn::Postgres(_) => DataBase::Postgres,
+            RdbcConn::Mysql(_) => DataBase::Mysql,
+            RdbcConn::Sqlite(_) => DataBase::Sqlite,
+            RdbcConn::Oracle(_) => DataBase::Oracle,
+        }
+    }
+    /// 连接对应的数据库类型
+    pub(crate) fn db_type(&self) -> RdbcDbType {
+        match self {
+            RdbcConn::Postgres(_) => RdbcDbType::Postgres,
+            RdbcConn::Mysql(_) => RdbcDbType::Mysql,
+            RdbcConn::Sqlite(_) => RdbcDbType::Sqlite,
+            RdbcConn::Oracle(_) => RdbcDbType::Oracle,
+        }
+    }
+    pub(crate) async fn execute_ddl_by_wrapper(&mut self, ddl: &RdbcDdlWrapper) -> OrmResp<()> {
+        let (sql, params) = render_ddl(ddl, self.database());
+        self.execute_ddl(&sql, &params).await
+    }
+    /// 执行 DDL 脚本，多条语句按分号拆分
+    /// PostgreSQL/SQLite 在同一事务中执行，MySQL/Oracle 的 DDL 隐式提交，逐条执行
+    /// 带绑定参数时只允许单条语句
+    pub(crate) async fn execute_ddl(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<()> {
+        let statements = split_sql_statements(sql.as_str(), &self.db_type());
+        if !params.is_empty() {
+            if statements.len() != 1 {
+                return Err(OrmError::new(
+                    OrmErrorKind::ScriptError,
+                    "带参数的DDL只能包含一条语句".to_string(),
+                ));
+            }
+            self.execute_sql(&statements[0], params).await?;
+            return Ok(());
+        }
+        if statements.is_empty() {
+            return Ok(());
+        }
         match self {
-            RdbcTransaction::Postgres(c) => c.commit().await,
+            RdbcConn::Postgres(c) => c.execute_ddl_statements(&statements).await,
+            RdbcConn::Mysql(c) => c.execute_ddl_statements(&statements).await,
+            RdbcConn::Sqlite(c) => c.execute_ddl_statements(&statements).await,
+            RdbcConn::Oracle(c) => c.execute_ddl_statements(&statements).await,
         }
     }
 }
@@ -1,20 +1,50 @@
 use bb8::RunError;
 use bb8_oracle;
+use rusqlite::{ffi, ErrorCode};
 use serde::{Deserialize, Serialize};
+use std::error::Error;
 use std::fmt::Display;
+use std::sync::Arc;
 use tokio_postgres;
+use tokio_postgres::error::SqlState;
 
+/// 数据库操作错误
+///
+/// 携带驱动原始错误，无法再通过结构体字面量构造，
+/// 请使用 [`OrmError::new`] 创建，必要时通过 [`OrmError::with_source`] 附加原始错误
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct OrmError {
     pub kind: OrmErrorKind,
     pub msg: String,
+    /// 数据库原始错误码：Postgres 为 SQLSTATE，MySQL/Oracle/Sqlite 为错误号
+    #[serde(default)]
+    pub code: Option<String>,
+    /// 违反的约束名称
+    #[serde(default)]
+    pub constraint: Option<String>,
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub column: Option<String>,
+    /// 驱动返回的原始错误
+    #[serde(skip)]
+    source: Option<Arc<dyn Error + Send + Sync>>,
 }
 impl Display for OrmError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", format!("[{}]{}", self.kind.to_string(), self.msg))
+        write!(f, "[{}]{}", self.kind, self.msg)
     }
 }
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl Error for OrmError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(source.as_ref() as &(dyn Error + 'static)),
+            None => None,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum OrmErrorKind {
     SqlError,
     PoolError,
@@ -22,6 +52,22 @@ pub enum OrmErrorKind {
     ConnError,
     NotSupport,
     NotImplement,
+    ScriptError,
+    MapperError,
+    TransactionError,
+    /// 串行化冲突，如 Postgres 的 SQLSTATE 40001
+    SerializationFailure,
+    /// 死锁，如 Postgres 的 SQLSTATE 40P01、MySQL 的 1213
+    Deadlock,
+    UniqueViolation,
+    ForeignKeyViolation,
+    NotNullViolation,
+    /// 语句超时、锁等待超时或获取连接超时
+    Timeout,
+    /// 连接已断开
+    ConnectionLost,
+    /// 配置文件、环境变量或连接地址中的配置错误
+    ConfigError,
     Other,
 }
 
@@ -35,6 +81,17 @@ impl Display for OrmErrorKind {
             OrmErrorKind::Other => "Other".to_string(),
             OrmErrorKind::NotSupport => "NotSupport".to_string(),
             OrmErrorKind::NotImplement => "NotImplement".to_string(),
+            OrmErrorKind::ScriptError => "ScriptError".to_string(),
+            OrmErrorKind::MapperError => "MapperError".to_string(),
+            OrmErrorKind::TransactionError => "TransactionError".to_string(),
+            OrmErrorKind::SerializationFailure => "SerializationFailure".to_string(),
+            OrmErrorKind::Deadlock => "Deadlock".to_string(),
+            OrmErrorKind::UniqueViolation => "UniqueViolation".to_string(),
+            OrmErrorKind::ForeignKeyViolation => "ForeignKeyViolation".to_string(),
+            OrmErrorKind::NotNullViolation => "NotNullViolation".to_string(),
+            OrmErrorKind::Timeout => "Timeout".to_string(),
+            OrmErrorKind::ConnectionLost => "ConnectionLost".to_string(),
+            OrmErrorKind::ConfigError => "ConfigError".to_string(),
         };
         write!(f, "{}", str)
     }
@@ -42,45 +99,293 @@ impl Display for OrmErrorKind {
 
 pub type OrmResp<T> = Result<T, OrmError>;
 
+impl OrmError {
+    pub fn new(kind: OrmErrorKind, msg: String) -> Self {
+        OrmError {
+            kind,
+            msg,
+            code: None,
+            constraint: None,
+            table: None,
+            column: None,
+            source: None,
+        }
+    }
+    /// 串行化冲突及死锁可通过重新执行事务解决
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            OrmErrorKind::SerializationFailure | OrmErrorKind::Deadlock
+        )
+    }
+}
+
+impl OrmError {
+    fn with_code(mut self, code: impl ToString) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+    pub(crate) fn into_conn_error(mut self) -> Self {
+        if self.kind == OrmErrorKind::SqlError {
+            self.kind = OrmErrorKind::ConnError;
+        }
+        self
+    }
+    /// 附加原始错误，可通过 [`Error::source`] 取回
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+}
+
 impl From<tokio_postgres::Error> for OrmError {
     fn from(value: tokio_postgres::Error) -> Self {
-        OrmError {
-            kind: OrmErrorKind::SqlError,
-            msg: value.to_string(),
+        let mut err = OrmError::new(OrmErrorKind::SqlError, value.to_string());
+        if let Some(db_err) = value.as_db_error() {
+            let code = db_err.code();
+            err.kind = if *code == SqlState::UNIQUE_VIOLATION {
+                OrmErrorKind::UniqueViolation
+            } else if *code == SqlState::FOREIGN_KEY_VIOLATION {
+                OrmErrorKind::ForeignKeyViolation
+            } else if *code == SqlState::NOT_NULL_VIOLATION {
+                OrmErrorKind::NotNullViolation
+            } else if *code == SqlState::T_R_SERIALIZATION_FAILURE {
+                OrmErrorKind::SerializationFailure
+            } else if *code == SqlState::T_R_DEADLOCK_DETECTED {
+                OrmErrorKind::Deadlock
+            } else if *code == SqlState::QUERY_CANCELED
+                || *code == SqlState::LOCK_NOT_AVAILABLE
+                || *code == SqlState::IDLE_IN_TRANSACTION_SESSION_TIMEOUT
+            {
+                OrmErrorKind::Timeout
+            } else if code.code().starts_with("08")
+                || *code == SqlState::ADMIN_SHUTDOWN
+                || *code == SqlState::CRASH_SHUTDOWN
+            {
+                OrmErrorKind::ConnectionLost
+            } else {
+                OrmErrorKind::SqlError
+            };
+            err.code = Some(code.code().to_string());
+            err.constraint = db_err.constraint().map(|v| v.to_string());
+            err.table = db_err.table().map(|v| v.to_string());
+            err.column = db_err.column().map(|v| v.to_string());
+        } else if value.is_closed() {
+            err.kind = OrmErrorKind::ConnectionLost;
         }
+        err.with_source(value)
     }
 }
-impl From<RunError<tokio_postgres::Error>> for OrmError {
-    fn from(value: RunError<tokio_postgres::Error>) -> Self {
-        OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: value.to_string(),
+/// 从连接池获取连接失败，未能归类的驱动错误视为连接错误
+impl<E> From<RunError<E>> for OrmError
+where
+    E: Into<OrmError>,
+{
+    fn from(value: RunError<E>) -> Self {
+        match value {
+            RunError::User(err) => err.into().into_conn_error(),
+            RunError::TimedOut => acquire_timeout_error(),
         }
     }
 }
 
 impl From<bb8_oracle::oracle::Error> for OrmError {
+    /// oracle 驱动各版本的错误结构不同，错误码从 ORA-/DPI- 前缀的错误信息中解析
     fn from(value: bb8_oracle::oracle::Error) -> Self {
-        OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: value.to_string(),
+        let msg = value.to_string();
+        let mut err = OrmError::new(OrmErrorKind::SqlError, msg.clone());
+        if let Some(code) = vendor_code(msg.as_str(), "ORA-") {
+            err.kind = match code {
+                1 => OrmErrorKind::UniqueViolation,
+                2291 | 2292 => OrmErrorKind::ForeignKeyViolation,
+                1400 | 1407 => OrmErrorKind::NotNullViolation,
+                8177 => OrmErrorKind::SerializationFailure,
+                60 => OrmErrorKind::Deadlock,
+                1013 | 30006 | 54 => OrmErrorKind::Timeout,
+                3113 | 3114 | 3135 | 12170 | 12541 | 12537 | 28 => OrmErrorKind::ConnectionLost,
+                _ => OrmErrorKind::SqlError,
+            };
+            match err.kind {
+                OrmErrorKind::UniqueViolation | OrmErrorKind::ForeignKeyViolation => {
+                    // unique constraint (SCHEMA.NAME) violated
+                    err.constraint = between(msg.as_str(), "(", ")").map(|v| v.to_string());
+                }
+                OrmErrorKind::NotNullViolation => {
+                    // cannot insert NULL into ("SCHEMA"."TABLE"."COLUMN")
+                    if let Some(path) = between(msg.as_str(), "(", ")") {
+                        let names: Vec<&str> =
+                            path.split('.').map(|v| v.trim_matches('"')).collect();
+                        if names.len() >= 2 {
+                            err.table = Some(names[names.len() - 2].to_string());
+                            err.column = Some(names[names.len() - 1].to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+            err.code = Some(format!("ORA-{:05}", code));
+        } else if let Some(code) = vendor_code(msg.as_str(), "DPI-") {
+            // DPI-1067: 调用超时，DPI-1080: 连接已断开
+            err.kind = match code {
+                1067 => OrmErrorKind::Timeout,
+                1010 | 1080 => OrmErrorKind::ConnectionLost,
+                _ => OrmErrorKind::SqlError,
+            };
+            err.code = Some(format!("DPI-{:04}", code));
         }
+        err.with_source(value)
     }
 }
 impl From<bb8_oracle::Error> for OrmError {
     fn from(value: bb8_oracle::Error) -> Self {
-        OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: value.to_string(),
+        OrmError::new(OrmErrorKind::PoolError, value.to_string())
+    }
+}
+
+impl From<mysql_async::Error> for OrmError {
+    fn from(value: mysql_async::Error) -> Self {
+        let mut err = OrmError::new(OrmErrorKind::SqlError, value.to_string());
+        match &value {
+            mysql_async::Error::Server(server_err) => {
+                let msg = server_err.message.as_str();
+                err.kind = match server_err.code {
+                    // ER_DUP_ENTRY: Duplicate entry 'x' for key 'table.key'
+                    1062 => {
+                        if let Some(key) = between(msg, "for key '", "'") {
+                            match key.rsplit_once('.') {
+                                Some((table, name)) => {
+                                    err.table = Some(table.to_string());
+                                    err.constraint = Some(name.to_string());
+                                }
+                                None => err.constraint = Some(key.to_string()),
+                            }
+                        }
+                        OrmErrorKind::UniqueViolation
+                    }
+                    // ER_ROW_IS_REFERENCED_2 / ER_NO_REFERENCED_ROW_2:
+                    // ... fails (`db`.`table`, CONSTRAINT `name` FOREIGN KEY ...)
+                    1451 | 1452 => {
+                        err.table = between(msg, "`.`", "`").map(|v| v.to_string());
+                        err.constraint = between(msg, "CONSTRAINT `", "`").map(|v| v.to_string());
+                        OrmErrorKind::ForeignKeyViolation
+                    }
+                    // ER_BAD_NULL_ERROR: Column 'x' cannot be null
+                    // ER_NO_DEFAULT_FOR_FIELD: Field 'x' doesn't have a default value
+                    1048 | 1364 => {
+                        err.column = between(msg, "'", "'").map(|v| v.to_string());
+                        OrmErrorKind::NotNullViolation
+                    }
+                    1213 => OrmErrorKind::Deadlock,
+                    // ER_LOCK_WAIT_TIMEOUT / ER_QUERY_TIMEOUT
+                    1205 | 3024 => OrmErrorKind::Timeout,
+                    // ER_SERVER_SHUTDOWN
+                    1053 => OrmErrorKind::ConnectionLost,
+                    // ER_CON_COUNT_ERROR: Too many connections
+                    1040 => OrmErrorKind::ConnError,
+                    _ => OrmErrorKind::SqlError,
+                };
+                err.code = Some(server_err.code.to_string());
+            }
+            mysql_async::Error::Io(_)
+            | mysql_async::Error::Driver(mysql_async::DriverError::ConnectionClosed) => {
+                err.kind = OrmErrorKind::ConnectionLost;
+            }
+            _ => {}
         }
+        err.with_source(value)
     }
 }
 
 impl From<rusqlite::Error> for OrmError {
     fn from(value: rusqlite::Error) -> Self {
-        OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: value.to_string(),
+        let mut err = OrmError::new(OrmErrorKind::SqlError, value.to_string());
+        match &value {
+            rusqlite::Error::SqliteFailure(ffi_err, msg) => {
+                err.kind = match ffi_err.extended_code {
+                    ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY => {
+                        OrmErrorKind::UniqueViolation
+                    }
+                    ffi::SQLITE_CONSTRAINT_FOREIGNKEY => OrmErrorKind::ForeignKeyViolation,
+                    ffi::SQLITE_CONSTRAINT_NOTNULL => OrmErrorKind::NotNullViolation,
+                    _ => match ffi_err.code {
+                        ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => {
+                            OrmErrorKind::Timeout
+                        }
+                        _ => OrmErrorKind::SqlError,
+                    },
+                };
+                // UNIQUE constraint failed: table.column
+                if let Some(target) = msg.as_deref().and_then(|m| m.split_once("failed: ")) {
+                    let first = target.1.split(',').next().unwrap_or("").trim();
+                    if let Some((table, column)) = first.split_once('.') {
+                        err.table = Some(table.to_string());
+                        err.column = Some(column.to_string());
+                    }
+                }
+                err = err.with_code(ffi_err.extended_code);
+            }
+            rusqlite::Error::FromSqlConversionFailure(..)
+            | rusqlite::Error::IntegralValueOutOfRange(..)
+            | rusqlite::Error::InvalidColumnType(..)
+            | rusqlite::Error::InvalidColumnIndex(..)
+            | rusqlite::Error::InvalidColumnName(..) => {
+                err.kind = OrmErrorKind::DataError;
+            }
+            _ => {}
         }
+        err.with_source(value)
+    }
+}
+
+pub(crate) fn acquire_timeout_error() -> OrmError {
+    OrmError::new(OrmErrorKind::Timeout, "获取数据库连接超时".to_string())
+}
+
+/// 从错误信息中解析 ORA-00001 形式的错误号
+fn vendor_code(msg: &str, prefix: &str) -> Option<u32> {
+    let start = msg.find(prefix)? + prefix.len();
+    let digits: String = msg[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+/// 取 start 与 end 之间的内容
+fn between<'a>(msg: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = msg.find(start)? + start.len();
+    let len = msg[from..].find(end)?;
+    Some(&msg[from..from + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mysql_server_error(code: u16) -> OrmError {
+        OrmError::from(mysql_async::Error::Server(mysql_async::ServerError {
+            code,
+            message: "server error".to_string(),
+            state: "HY000".to_string(),
+        }))
+    }
+
+    #[test]
+    fn mysql_connection_errors() {
+        let err = mysql_server_error(1053);
+        assert_eq!(err.kind, OrmErrorKind::ConnectionLost);
+        assert_eq!(err.code.as_deref(), Some("1053"));
+        assert!(err.source().is_some());
+        assert_eq!(mysql_server_error(1040).kind, OrmErrorKind::ConnError);
+    }
+
+    #[test]
+    fn with_source_keeps_original_error() {
+        let io = std::io::Error::other("io failed");
+        let err = OrmError::new(OrmErrorKind::Other, "wrapped".to_string()).with_source(io);
+        assert_eq!(
+            err.source().map(|e| e.to_string()).as_deref(),
+            Some("io failed")
+        );
     }
 }
@@ -0,0 +1,60 @@
+use crate::error::OrmResp;
+use crate::registry::default_orm;
+use crate::{PageData, RdbcOrmRow, RdbcTransaction, RdbcTransactionFuture};
+use bmbp_sql::{RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper};
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// 使用默认数据源分页查询
+/// 执行期间只持有实例的引用，不持有注册表的锁，重新加载数据源不会等待查询结束
+pub async fn find_page<T>(
+    query: &RdbcQueryWrapper,
+    page_num: usize,
+    page_size: usize,
+) -> OrmResp<PageData<T>>
+where
+    T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+{
+    default_orm()?
+        .find_page_by_query(query, page_num, page_size)
+        .await
+}
+
+/// 使用默认数据源查询列表
+pub async fn find_list<T>(query: &RdbcQueryWrapper) -> OrmResp<Vec<T>>
+where
+    T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+{
+    default_orm()?.find_list_by_query(query).await
+}
+
+/// 使用默认数据源查询单条记录
+pub async fn find_one<T>(query: &RdbcQueryWrapper) -> OrmResp<Option<T>>
+where
+    T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+{
+    default_orm()?.find_one_by_query(query).await
+}
+
+/// 使用默认数据源新增
+pub async fn insert(insert: &RdbcInsertWrapper) -> OrmResp<usize> {
+    default_orm()?.execute_insert_by_wrapper(insert).await
+}
+
+/// 使用默认数据源修改
+pub async fn update(update: &RdbcUpdateWrapper) -> OrmResp<usize> {
+    default_orm()?.execute_update_by_wrapper(update).await
+}
+
+/// 使用默认数据源删除
+pub async fn delete(delete: &RdbcDeleteWrapper) -> OrmResp<usize> {
+    default_orm()?.execute_delete_by_wrapper(delete).await
+}
+
+/// 在默认数据源的事务中执行闭包，返回 Ok 时提交，返回 Err 或 panic 时回滚
+pub async fn transaction<F, R>(f: F) -> OrmResp<R>
+where
+    F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+{
+    default_orm()?.transaction(f).await
+}
@@ -1,32 +1,59 @@
+// 公开接口沿用 &String / &Vec 参数；OrmError 携带 SQLSTATE 及约束信息，体积较大
+#![allow(clippy::ptr_arg, clippy::result_large_err)]
+
+mod ambient;
 mod bean;
 mod client;
+mod config;
 mod conn;
 mod ds;
 pub mod error;
+mod global;
+mod mapper;
 mod orm;
 mod pool;
+mod registry;
+mod replica;
+mod retry;
+mod script;
+mod trans;
 
-use crate::error::{OrmError, OrmErrorKind, OrmResp};
+pub use ambient::Propagation;
 pub use bean::*;
+pub use config::{RdbcConfigLoader, DEFAULT_ENV_PREFIX};
 pub use conn::*;
-pub use ds::PoolConfig;
 pub use ds::RdbcDataSource;
 pub use ds::RdbcDbType;
+pub use ds::{ConnectRetry, PasswordCallback, PasswordProvider, PoolConfig, TlsConfig, TlsMode};
 pub use error::*;
+pub use global::{delete, find_list, find_one, find_page, insert, transaction, update};
+pub use mapper::*;
 pub use orm::RdbcOrm;
-pub use pool::RdbcPool;
-use std::sync::Arc;
-use tokio::sync::{OnceCell, RwLock};
+pub use pool::{PoolHealth, RdbcPool};
+pub use registry::{
+    add_orm, datasource_names, default_orm, init_datasource, orm, reload_datasource,
+    remove_datasource, replace_orm, set_default_datasource, shutdown, DefaultOrmCell,
+    DEFAULT_DATASOURCE,
+};
+pub use replica::{LoadBalance, ReplicaConfig};
+pub use retry::RetryPolicy;
+pub use script::{RdbcDynamicScript, RdbcScript};
+pub use trans::{IsolationLevel, RdbcTransaction, RdbcTransactionFuture, TransactionOptions};
 
-pub static BMBP_ORM: OnceCell<RwLock<RdbcOrm>> = OnceCell::const_new();
+/// 默认数据源，保留给使用 BMBP_ORM.get().unwrap().read().await 的调用方
+///
+/// # Panics
+/// 未设置默认数据源时 read、write 会 panic，需要处理该情况时使用 try_read 或 default_orm()
+#[deprecated(note = "使用 default_orm() 获取默认数据源，或使用 orm(name) 获取命名数据源")]
+pub static BMBP_ORM: DefaultOrmCell = DefaultOrmCell;
 
+/// 以 DEFAULT_DATASOURCE 为名注册数据源并设为默认数据源
 pub async fn init_bmbp_orm(ds: RdbcDataSource) -> OrmResp<()> {
-    let orm = RdbcOrm::new(Arc::new(ds)).await?;
-    match BMBP_ORM.set(RwLock::new(orm)) {
-        Ok(v) => Ok(()),
-        Err(err) => Err(OrmError {
-            kind: OrmErrorKind::PoolError,
-            msg: err.to_string(),
-        }),
-    }
+    init_datasource(DEFAULT_DATASOURCE, ds).await?;
+    set_default_datasource(DEFAULT_DATASOURCE)
+}
+
+/// 使用新的数据源配置重建 init_bmbp_orm 注册的数据源，用于修改密码或连接池大小
+pub async fn reload_bmbp_orm(ds: RdbcDataSource) -> OrmResp<()> {
+    reload_datasource(DEFAULT_DATASOURCE, ds).await
 }
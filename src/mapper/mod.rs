@@ -0,0 +1,405 @@
+mod xml;
+mod yaml;
+
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::script::RdbcDynamicScript;
+use bmbp_sql::RdbcValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 语句类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RdbcStatementKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Ddl,
+}
+
+/// 参数方式
+/// Named: 使用 #{name} 命名参数及动态标签，按脚本方式执行
+/// Positional: 语句已使用目标数据库的位置参数，按 params 声明的顺序从参数表中取值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RdbcParamStyle {
+    #[default]
+    Named,
+    Positional,
+}
+
+/// 返回值类型，查询语句为 List/One/Page，其余语句为 Affected
+/// 查询语句声明了返回值类型时只能通过对应的 find_list/find_one/find_page_by_name 执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RdbcResultKind {
+    List,
+    One,
+    Page,
+    Affected,
+}
+
+/// 映射文件中的命名语句
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RdbcStatement {
+    pub id: String,
+    pub kind: RdbcStatementKind,
+    #[serde(default)]
+    pub param_style: RdbcParamStyle,
+    #[serde(default)]
+    pub result: Option<RdbcResultKind>,
+    #[serde(default)]
+    pub params: Vec<String>,
+    pub sql: String,
+}
+
+impl RdbcStatement {
+    pub fn result_kind(&self) -> RdbcResultKind {
+        match (self.result, self.kind) {
+            (Some(result), _) => result,
+            (None, RdbcStatementKind::Select) => RdbcResultKind::List,
+            (None, _) => RdbcResultKind::Affected,
+        }
+    }
+    pub fn is_query(&self) -> bool {
+        self.kind == RdbcStatementKind::Select
+    }
+
+    /// 位置参数语句按 params 声明的顺序取参数值
+    pub(crate) fn positional_values(
+        &self,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<Vec<RdbcValue>> {
+        let mut values = vec![];
+        for name in self.params.iter() {
+            match params.get(name) {
+                Some(value) => values.push(value.clone()),
+                None => {
+                    return Err(OrmError::new(
+                        OrmErrorKind::ScriptError,
+                        format!("语句{}参数缺失: {}", self.id, name),
+                    ));
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    fn validate(&self, source: &Path) -> OrmResp<()> {
+        let fail = |msg: String| {
+            Err(OrmError::new(
+                OrmErrorKind::MapperError,
+                format!("{}[{}]: {}", source.display(), self.id, msg),
+            ))
+        };
+        if self.id.trim().is_empty() {
+            return fail("语句id不能为空".to_string());
+        }
+        if self.sql.trim().is_empty() {
+            return fail("语句内容不能为空".to_string());
+        }
+        let result = self.result_kind();
+        if self.is_query() == (result == RdbcResultKind::Affected) {
+            return fail(format!(
+                "返回值类型{:?}与语句类型{:?}不匹配",
+                result, self.kind
+            ));
+        }
+        match self.param_style {
+            RdbcParamStyle::Named => {
+                if !self.params.is_empty() {
+                    return fail("命名参数语句无需声明params".to_string());
+                }
+                if let Err(err) = RdbcDynamicScript::parse(self.sql.as_str()) {
+                    return fail(err.msg);
+                }
+            }
+            RdbcParamStyle::Positional => {
+                if self.sql.contains("#{") {
+                    return fail("位置参数语句不能包含#{}命名参数".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 命名语句注册表，语句名为 `命名空间.id`
+#[derive(Debug, Clone, Default)]
+pub struct RdbcMapperRegistry {
+    statements: HashMap<String, RdbcStatement>,
+}
+
+impl RdbcMapperRegistry {
+    pub fn new() -> Self {
+        RdbcMapperRegistry::default()
+    }
+
+    /// 递归加载目录下的 .yaml/.yml/.xml 映射文件，返回加载的语句数量
+    /// 任一文件加载失败时不加载目录中的任何语句
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> OrmResp<usize> {
+        let mut files = vec![];
+        collect_mapper_files(dir.as_ref(), &mut files)?;
+        files.sort();
+        let mut staged = self.clone();
+        let mut count = 0;
+        for file in files {
+            count += staged.load_file(&file)?;
+        }
+        *self = staged;
+        Ok(count)
+    }
+
+    /// 加载单个映射文件，返回加载的语句数量
+    /// 全部语句校验通过且没有重复定义时才加入注册表
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> OrmResp<usize> {
+        let path = path.as_ref();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                return Err(OrmError::new(
+                    OrmErrorKind::MapperError,
+                    format!("{}: {}", path.display(), err),
+                ));
+            }
+        };
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let (namespace, statements) = match extension.as_str() {
+            "yaml" | "yml" => yaml::parse_yaml_mapper(content.as_str()),
+            "xml" => xml::parse_xml_mapper(content.as_str()),
+            _ => {
+                return Err(OrmError::new(
+                    OrmErrorKind::MapperError,
+                    format!("{}: 不支持的映射文件类型", path.display()),
+                ));
+            }
+        }
+        .map_err(|err| {
+            OrmError::new(
+                OrmErrorKind::MapperError,
+                format!("{}: {}", path.display(), err.msg),
+            )
+        })?;
+        let mut loaded: HashMap<String, RdbcStatement> = HashMap::new();
+        for mut statement in statements {
+            statement.validate(path)?;
+            if !namespace.is_empty() {
+                statement.id = format!("{}.{}", namespace, statement.id);
+            }
+            if self.statements.contains_key(&statement.id) || loaded.contains_key(&statement.id) {
+                return Err(OrmError::new(
+                    OrmErrorKind::MapperError,
+                    format!("{}: 语句重复定义: {}", path.display(), statement.id),
+                ));
+            }
+            loaded.insert(statement.id.clone(), statement);
+        }
+        let count = loaded.len();
+        self.statements.extend(loaded);
+        Ok(count)
+    }
+
+    pub fn add(&mut self, statement: RdbcStatement) -> OrmResp<()> {
+        if self.statements.contains_key(&statement.id) {
+            return Err(OrmError::new(
+                OrmErrorKind::MapperError,
+                format!("语句重复定义: {}", statement.id),
+            ));
+        }
+        self.statements.insert(statement.id.clone(), statement);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> OrmResp<&RdbcStatement> {
+        match self.statements.get(name) {
+            Some(statement) => Ok(statement),
+            None => Err(OrmError::new(
+                OrmErrorKind::MapperError,
+                format!("未找到语句: {}", name),
+            )),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+}
+
+fn collect_mapper_files(dir: &Path, files: &mut Vec<PathBuf>) -> OrmResp<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return Err(OrmError::new(
+                OrmErrorKind::MapperError,
+                format!("{}: {}", dir.display(), err),
+            ));
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_mapper_files(&path, files)?;
+            continue;
+        }
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if matches!(extension.as_str(), "yaml" | "yml" | "xml") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapper_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bmbp_orm_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for (file, content) in files {
+            fs::write(dir.join(file), content).unwrap();
+        }
+        dir
+    }
+
+    fn statement(kind: RdbcStatementKind, sql: &str) -> RdbcStatement {
+        RdbcStatement {
+            id: "s".to_string(),
+            kind,
+            param_style: RdbcParamStyle::Named,
+            result: None,
+            params: vec![],
+            sql: sql.to_string(),
+        }
+    }
+
+    #[test]
+    fn load_yaml_and_xml() {
+        let dir = mapper_dir(
+            "mapper_load",
+            &[
+                (
+                    "user.yaml",
+                    "namespace: user\nstatements:\n  - id: list\n    kind: select\n    sql: SELECT * FROM t_user\n",
+                ),
+                (
+                    "sub/role.xml",
+                    "<mapper namespace=\"role\"><delete id=\"remove\">DELETE FROM t_role WHERE id = #{id}</delete></mapper>",
+                ),
+                ("readme.txt", "ignored"),
+            ],
+        );
+        let mut registry = RdbcMapperRegistry::new();
+        assert_eq!(registry.load_dir(&dir).unwrap(), 2);
+        let list = registry.get("user.list").unwrap();
+        assert_eq!(list.result_kind(), RdbcResultKind::List);
+        let remove = registry.get("role.remove").unwrap();
+        assert_eq!(remove.kind, RdbcStatementKind::Delete);
+        assert_eq!(remove.result_kind(), RdbcResultKind::Affected);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn duplicate_ids_reject_whole_dir() {
+        let dir = mapper_dir(
+            "mapper_duplicate",
+            &[
+                (
+                    "a.yaml",
+                    "namespace: user\nstatements:\n  - id: list\n    kind: select\n    sql: SELECT 1\n",
+                ),
+                (
+                    "b.xml",
+                    "<mapper namespace=\"user\"><select id=\"list\">SELECT 2</select></mapper>",
+                ),
+            ],
+        );
+        let mut registry = RdbcMapperRegistry::new();
+        let err = registry.load_dir(&dir).unwrap_err();
+        assert_eq!(err.kind, OrmErrorKind::MapperError);
+        assert!(registry.is_empty());
+
+        fs::remove_file(dir.join("b.xml")).unwrap();
+        fs::write(
+            dir.join("a.yaml"),
+            "namespace: user\nstatements:\n  - id: list\n    kind: select\n    sql: SELECT 1\n  - id: list\n    kind: select\n    sql: SELECT 2\n",
+        )
+        .unwrap();
+        assert!(registry.load_dir(&dir).is_err());
+        assert!(registry.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn result_kind_must_match_statement() {
+        let source = Path::new("test.xml");
+        let mut select = statement(RdbcStatementKind::Select, "SELECT 1");
+        select.result = Some(RdbcResultKind::Affected);
+        assert!(select.validate(source).is_err());
+        let mut update = statement(RdbcStatementKind::Update, "UPDATE t SET a = 1");
+        update.result = Some(RdbcResultKind::One);
+        assert!(update.validate(source).is_err());
+        update.result = Some(RdbcResultKind::Affected);
+        assert!(update.validate(source).is_ok());
+        assert!(statement(RdbcStatementKind::Select, " ")
+            .validate(source)
+            .is_err());
+        // 动态标签未闭合或闭合顺序错误
+        let unclosed = statement(
+            RdbcStatementKind::Select,
+            "SELECT * FROM t <where><if test=\"a != null\">a = #{a}</where>",
+        );
+        assert!(unclosed.validate(source).is_err());
+        let mismatched = statement(
+            RdbcStatementKind::Select,
+            "SELECT * FROM t <where><if test=\"a != null\">a = #{a}</where></if>",
+        );
+        assert!(mismatched.validate(source).is_err());
+    }
+
+    #[test]
+    fn positional_params() {
+        let source = Path::new("test.yaml");
+        let mut insert = statement(
+            RdbcStatementKind::Insert,
+            "INSERT INTO t(a, b) VALUES ($1, $2)",
+        );
+        insert.params = vec!["a".to_string(), "b".to_string()];
+        // 命名参数语句不能声明 params
+        assert!(insert.validate(source).is_err());
+        insert.param_style = RdbcParamStyle::Positional;
+        assert!(insert.validate(source).is_ok());
+
+        let params = HashMap::from([
+            ("b".to_string(), RdbcValue::Int(2)),
+            ("a".to_string(), RdbcValue::Int(1)),
+        ]);
+        assert_eq!(
+            insert.positional_values(&params).unwrap(),
+            vec![RdbcValue::Int(1), RdbcValue::Int(2)]
+        );
+        let err = insert
+            .positional_values(&HashMap::from([("a".to_string(), RdbcValue::Int(1))]))
+            .unwrap_err();
+        assert_eq!(err.kind, OrmErrorKind::ScriptError);
+
+        insert.sql = "INSERT INTO t(a) VALUES (#{a})".to_string();
+        assert!(insert.validate(source).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::mapper::{RdbcParamStyle, RdbcResultKind, RdbcStatement, RdbcStatementKind};
+use std::collections::HashMap;
+
+/// XML 映射文件
+/// ```xml
+/// <mapper namespace="user">
+///     <select id="search" result="page">
+///         SELECT * FROM t_user
+///         <where><if test="name != null">AND name = #{name}</if></where>
+///     </select>
+///     <insert id="add" paramStyle="positional" params="name,age">
+///         INSERT INTO t_user(name, age) VALUES ($1, $2)
+///     </insert>
+/// </mapper>
+/// ```
+/// 语句内容中的 `&lt;` 等实体会被还原，`<![CDATA[...]]>` 中的内容保持原样
+pub(crate) fn parse_xml_mapper(content: &str) -> OrmResp<(String, Vec<RdbcStatement>)> {
+    let content = strip_comments(content);
+    let start = match content.find("<mapper") {
+        Some(start) => start,
+        None => return Err(xml_error("缺少<mapper>根节点".to_string())),
+    };
+    let (mapper_attrs, mut rest) = parse_open_tag(&content[start + "<mapper".len()..])?;
+    let end = match rest.rfind("</mapper>") {
+        Some(end) => end,
+        None => return Err(xml_error("<mapper>未闭合".to_string())),
+    };
+    rest = &rest[..end];
+    let namespace = mapper_attrs.get("namespace").cloned().unwrap_or_default();
+
+    let mut statements = vec![];
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let tag_body = match rest.strip_prefix('<') {
+            Some(tag_body) => tag_body,
+            None => return Err(xml_error(format!("无法识别的内容: {}", head(rest)))),
+        };
+        let tag_len = tag_body
+            .find(|c: char| c.is_whitespace() || c == '>')
+            .unwrap_or(tag_body.len());
+        let tag = &tag_body[..tag_len];
+        let kind = match tag {
+            "select" => RdbcStatementKind::Select,
+            "insert" => RdbcStatementKind::Insert,
+            "update" => RdbcStatementKind::Update,
+            "delete" => RdbcStatementKind::Delete,
+            "ddl" => RdbcStatementKind::Ddl,
+            _ => return Err(xml_error(format!("不支持的语句标签: <{}>", tag))),
+        };
+        let (attrs, body) = parse_open_tag(&tag_body[tag_len..])?;
+        let close_tag = format!("</{}>", tag);
+        let close = match find_outside_cdata(body, close_tag.as_str()) {
+            Some(close) => close,
+            None => return Err(xml_error(format!("<{}>未闭合", tag))),
+        };
+        statements.push(build_statement(kind, &attrs, &body[..close])?);
+        rest = &body[close + close_tag.len()..];
+    }
+    Ok((namespace, statements))
+}
+
+fn build_statement(
+    kind: RdbcStatementKind,
+    attrs: &HashMap<String, String>,
+    body: &str,
+) -> OrmResp<RdbcStatement> {
+    let id = attrs.get("id").cloned().unwrap_or_default();
+    let param_style = match attrs
+        .get("paramStyle")
+        .or(attrs.get("param_style"))
+        .map(|v| v.as_str())
+    {
+        None | Some("named") => RdbcParamStyle::Named,
+        Some("positional") => RdbcParamStyle::Positional,
+        Some(v) => return Err(xml_error(format!("{}: 不支持的参数方式{}", id, v))),
+    };
+    let result = match attrs.get("result").map(|v| v.as_str()) {
+        None => None,
+        Some("list") => Some(RdbcResultKind::List),
+        Some("one") => Some(RdbcResultKind::One),
+        Some("page") => Some(RdbcResultKind::Page),
+        Some("affected") => Some(RdbcResultKind::Affected),
+        Some(v) => return Err(xml_error(format!("{}: 不支持的返回值类型{}", id, v))),
+    };
+    let params = attrs
+        .get("params")
+        .map(|v| {
+            v.split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(RdbcStatement {
+        id,
+        kind,
+        param_style,
+        result,
+        params,
+        sql: decode_body(body).trim().to_string(),
+    })
+}
+
+fn xml_error(msg: String) -> OrmError {
+    OrmError::new(OrmErrorKind::MapperError, msg)
+}
+
+fn head(src: &str) -> String {
+    src.chars().take(20).collect()
+}
+
+/// 去除注释及 XML 声明
+fn strip_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    loop {
+        let comment = rest.find("<!--");
+        let prolog = rest.find("<?");
+        let (start, end_mark) = match (comment, prolog) {
+            (Some(c), Some(p)) if p < c => (p, "?>"),
+            (Some(c), _) => (c, "-->"),
+            (None, Some(p)) => (p, "?>"),
+            (None, None) => break,
+        };
+        result.push_str(&rest[..start]);
+        match rest[start..].find(end_mark) {
+            Some(end) => rest = &rest[start + end + end_mark.len()..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 解析开始标签的属性，返回属性及标签之后的内容
+fn parse_open_tag(src: &str) -> OrmResp<(HashMap<String, String>, &str)> {
+    let mut quote: Option<char> = None;
+    let mut end = None;
+    for (idx, c) in src.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => {
+                end = Some(idx);
+                break;
+            }
+            None => {}
+        }
+    }
+    let end = match end {
+        Some(end) => end,
+        None => return Err(xml_error(format!("标签未结束: {}", head(src)))),
+    };
+    let mut attrs = HashMap::new();
+    let mut rest = src[..end].trim();
+    while !rest.is_empty() {
+        let eq = match rest.find('=') {
+            Some(eq) => eq,
+            None => return Err(xml_error(format!("属性格式错误: {}", rest))),
+        };
+        let key = rest[..eq].trim().to_string();
+        let value_part = rest[eq + 1..].trim_start();
+        let quote = match value_part.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _ => return Err(xml_error(format!("属性值需使用引号: {}", key))),
+        };
+        let value_end = match value_part[1..].find(quote) {
+            Some(value_end) => value_end + 1,
+            None => return Err(xml_error(format!("属性值未闭合: {}", key))),
+        };
+        attrs.insert(key, decode_entities(&value_part[1..value_end]));
+        rest = value_part[value_end + 1..].trim_start();
+    }
+    Ok((attrs, &src[end + 1..]))
+}
+
+fn find_outside_cdata(src: &str, pattern: &str) -> Option<usize> {
+    let mut offset = 0;
+    loop {
+        let rest = &src[offset..];
+        let found = rest.find(pattern)?;
+        match rest.find("<![CDATA[") {
+            Some(cdata) if cdata < found => {
+                let cdata_end = rest[cdata..].find("]]>")?;
+                offset += cdata + cdata_end + 3;
+            }
+            _ => return Some(offset + found),
+        }
+    }
+}
+
+/// 还原语句内容：CDATA 原样保留，其余内容还原实体
+fn decode_body(body: &str) -> String {
+    let mut sql = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("<![CDATA[") {
+        sql.push_str(decode_entities(&rest[..start]).as_str());
+        let cdata = &rest[start + 9..];
+        match cdata.find("]]>") {
+            Some(end) => {
+                sql.push_str(&cdata[..end]);
+                rest = &cdata[end + 3..];
+            }
+            None => {
+                sql.push_str(cdata);
+                rest = "";
+            }
+        }
+    }
+    sql.push_str(decode_entities(rest).as_str());
+    sql
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_statements() {
+        let content = r#"<?xml version="1.0"?>
+            <!-- 用户 -->
+            <mapper namespace="user">
+                <select id="search" result="page">
+                    SELECT * FROM t_user WHERE age &gt; #{age}
+                    <![CDATA[ AND score < 60 ]]>
+                </select>
+                <insert id="add" paramStyle="positional" params="name, age">
+                    INSERT INTO t_user(name, age) VALUES ($1, $2)
+                </insert>
+            </mapper>"#;
+        let (namespace, statements) = parse_xml_mapper(content).unwrap();
+        assert_eq!(namespace, "user");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].id, "search");
+        assert_eq!(statements[0].kind, RdbcStatementKind::Select);
+        assert_eq!(statements[0].result, Some(RdbcResultKind::Page));
+        assert_eq!(
+            statements[0].sql,
+            "SELECT * FROM t_user WHERE age > #{age}\n                     AND score < 60"
+        );
+        assert_eq!(statements[1].param_style, RdbcParamStyle::Positional);
+        assert_eq!(statements[1].params, vec!["name", "age"]);
+    }
+
+    #[test]
+    fn unclosed_or_unknown_tags() {
+        let cases = [
+            "<select id=\"a\">SELECT 1</select>",
+            "<mapper><select id=\"a\">SELECT 1</select>",
+            "<mapper><select id=\"a\">SELECT 1</mapper>",
+            "<mapper><query id=\"a\">SELECT 1</query></mapper>",
+            "<mapper><select id=\"a\" result=\"map\">SELECT 1</select></mapper>",
+            "<mapper><select id=a>SELECT 1</select></mapper>",
+        ];
+        for content in cases {
+            let err = parse_xml_mapper(content).unwrap_err();
+            assert_eq!(err.kind, OrmErrorKind::MapperError, "{}", content);
+        }
+    }
+}
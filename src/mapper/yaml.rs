@@ -0,0 +1,29 @@
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::mapper::RdbcStatement;
+use serde::Deserialize;
+
+/// YAML 映射文件
+/// ```yaml
+/// namespace: user
+/// statements:
+///   - id: search
+///     kind: select
+///     result: page
+///     sql: |
+///       SELECT * FROM t_user
+///       <where><if test="name != null">AND name = #{name}</if></where>
+/// ```
+#[derive(Debug, Deserialize)]
+struct YamlMapper {
+    #[serde(default)]
+    namespace: String,
+    #[serde(default)]
+    statements: Vec<RdbcStatement>,
+}
+
+pub(crate) fn parse_yaml_mapper(content: &str) -> OrmResp<(String, Vec<RdbcStatement>)> {
+    match serde_yaml::from_str::<YamlMapper>(content) {
+        Ok(mapper) => Ok((mapper.namespace, mapper.statements)),
+        Err(err) => Err(OrmError::new(OrmErrorKind::MapperError, err.to_string())),
+    }
+}
@@ -1,31 +1,226 @@
-use crate::ds::RdbcDataSource;
-use crate::error::OrmResp;
-use crate::{PageData, RdbcConn, RdbcOrmRow, RdbcPool, RdbcTransaction};
+use crate::ambient::{never_in_transaction, run_with_ambient, AmbientTransaction, Propagation};
+use crate::bean::convert_page_data;
+use crate::ds::{RdbcDataSource, RdbcDbType};
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::mapper::{
+    RdbcMapperRegistry, RdbcParamStyle, RdbcResultKind, RdbcStatement, RdbcStatementKind,
+};
+use crate::replica::{force_primary, RdbcReplicas, ReplicaConfig};
+use crate::retry::RetryPolicy;
+use crate::trans::{run_in_transaction, RdbcTransactionFuture};
+use crate::{
+    PageData, PoolHealth, RdbcConn, RdbcOrmRow, RdbcPool, RdbcScript, RdbcTransaction,
+    TransactionOptions,
+};
 use bmbp_sql::{
+    render_ddl, render_delete, render_insert, render_query, render_update, DataBase,
     RdbcDdlWrapper, RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper,
     RdbcValue,
 };
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+/// RdbcOrm 实例序号，用于区分任务绑定的事务属于哪个实例
+static ORM_SEQ: AtomicUsize = AtomicUsize::new(1);
 
 pub struct RdbcOrm {
+    id: usize,
     pool: RdbcPool,
+    replicas: RdbcReplicas,
     datasource: Arc<RdbcDataSource>,
+    /// 命名语句，单独加锁以便实例注册后仍可加载
+    mapper: RwLock<RdbcMapperRegistry>,
 }
 
 impl RdbcOrm {
     pub async fn new(datasource: Arc<RdbcDataSource>) -> OrmResp<Self> {
+        RdbcOrm::with_replicas(datasource, vec![], ReplicaConfig::default()).await
+    }
+    /// 创建读写分离的实例，事务外的查询由从库执行，写入及事务由主库执行
+    pub async fn with_replicas(
+        datasource: Arc<RdbcDataSource>,
+        replicas: Vec<Arc<RdbcDataSource>>,
+        config: ReplicaConfig,
+    ) -> OrmResp<Self> {
         let pool = RdbcPool::new(datasource.clone()).await?;
+        let replicas = RdbcReplicas::new(&datasource, replicas, config).await?;
         Ok(RdbcOrm {
+            id: ORM_SEQ.fetch_add(1, Ordering::Relaxed),
             pool,
+            replicas,
             datasource: datasource.clone(),
+            mapper: RwLock::new(RdbcMapperRegistry::new()),
         })
     }
-    pub async fn get_conn(&self) -> OrmResp<RdbcConn> {
+    /// 使用新的主库配置重建实例，保留实例序号、从库、读写分离配置及已加载的命名语句
+    /// 实例序号不变，任务中已绑定到原实例的事务及强制主库设置对新实例继续生效
+    pub(crate) async fn rebuild(&self, datasource: Arc<RdbcDataSource>) -> OrmResp<Self> {
+        let (replicas, config) = self.replicas.settings();
+        let mut orm = RdbcOrm::with_replicas(datasource, replicas, config).await?;
+        orm.id = self.id;
+        *orm.mapper_mut() = self.mapper().clone();
+        Ok(orm)
+    }
+    /// 获取主库连接
+    pub async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
         self.pool.get_conn().await
     }
+    /// 检测主库连接并返回连接池状态
+    pub async fn health(&self) -> PoolHealth {
+        self.pool.health().await
+    }
+    /// 检测各从库连接并返回连接池状态
+    pub async fn replica_health(&self) -> Vec<PoolHealth> {
+        self.replicas.health().await
+    }
+    /// 主库及从库借出的连接数
+    pub(crate) fn in_use(&self) -> usize {
+        self.pool.in_use() + self.replicas.in_use()
+    }
+    /// 执行 fut，期间通过本实例执行的查询均使用主库，用于写入后立即读取的场景
+    pub async fn read_primary<Fut: Future>(&self, fut: Fut) -> Fut::Output {
+        force_primary(self.id, fut).await
+    }
+    /// 在事务中执行闭包，返回 Ok 时提交，返回 Err 或 panic 时回滚
+    /// ```ignore
+    /// let count = orm
+    ///     .transaction(|tx| {
+    ///         Box::pin(async move {
+    ///             tx.execute_insert_by_wrapper(&insert).await?;
+    ///             tx.execute_update_by_wrapper(&update).await
+    ///         })
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn transaction<F, R>(&self, f: F) -> OrmResp<R>
+    where
+        F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+    {
+        self.transaction_with(&TransactionOptions::default(), f)
+            .await
+    }
+    /// 按指定的隔离级别、只读等选项开启事务执行闭包
+    pub async fn transaction_with<F, R>(&self, options: &TransactionOptions, f: F) -> OrmResp<R>
+    where
+        F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+    {
+        let mut conn = self.get_conn().await?;
+        let mut trans = conn.get_transaction(options).await?;
+        let rs = run_in_transaction(&mut trans, f).await;
+        self.replicas.mark_write();
+        rs
+    }
+    /// 在事务中执行闭包，失败且满足重试条件时按策略等待后使用新事务重新执行
+    /// 闭包可能被执行多次，不应包含无法重复的副作用
+    pub async fn transaction_with_retry<F, R>(
+        &self,
+        options: &TransactionOptions,
+        policy: &RetryPolicy,
+        mut f: F,
+    ) -> OrmResp<R>
+    where
+        F: for<'t, 'c> FnMut(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+    {
+        let mut attempt = 1;
+        loop {
+            match self.transaction_with(options, &mut f).await {
+                Err(err) if attempt < policy.max_attempts && (policy.retry_if)(&err) => {
+                    let delay = policy.backoff(attempt);
+                    tracing::warn!(
+                        "事务第{}次执行失败，{}ms后重试: {}",
+                        attempt,
+                        delay.as_millis(),
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                rs => return rs,
+            }
+        }
+    }
+    /// 按传播方式执行闭包，闭包中通过本实例执行的语句自动加入当前任务绑定的事务
+    /// ```ignore
+    /// orm.transactional(Propagation::Required, || async {
+    ///     orm.execute_insert_by_wrapper(&insert).await?;
+    ///     save_log(&orm).await
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn transactional<F, Fut, R>(&self, propagation: Propagation, f: F) -> OrmResp<R>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = OrmResp<R>>,
+    {
+        self.transactional_with(propagation, &TransactionOptions::default(), f)
+            .await
+    }
+    /// 按传播方式及事务选项执行闭包，加入已有事务时忽略事务选项
+    pub async fn transactional_with<F, Fut, R>(
+        &self,
+        propagation: Propagation,
+        options: &TransactionOptions,
+        f: F,
+    ) -> OrmResp<R>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = OrmResp<R>>,
+    {
+        match (propagation, self.ambient()) {
+            (Propagation::Required | Propagation::Supports, Some(ambient)) => {
+                ambient.join(f()).await
+            }
+            (Propagation::Required | Propagation::RequiresNew, _) => {
+                let mut conn = self.get_conn().await?;
+                let mut trans = conn.get_transaction(options).await?;
+                let rs = run_with_ambient(&mut trans, self.id, f()).await;
+                self.replicas.mark_write();
+                rs
+            }
+            (Propagation::Never, Some(_)) => Err(never_in_transaction()),
+            (Propagation::Supports | Propagation::Never, None) => f().await,
+        }
+    }
+    /// 事务外的查询，配置了从库时由从库执行
+    async fn read<'a, F, Fut, R>(&'a self, f: F) -> OrmResp<R>
+    where
+        F: Fn(&'a RdbcPool) -> Fut,
+        Fut: Future<Output = OrmResp<R>>,
+    {
+        self.replicas.read(self.id, &self.pool, f).await
+    }
+    /// 当前任务绑定的本实例事务
+    fn ambient(&self) -> Option<AmbientTransaction> {
+        AmbientTransaction::current(self.id)
+    }
+    fn database(&self) -> DataBase {
+        match self.datasource.db_type {
+            RdbcDbType::Postgres => DataBase::Postgres,
+            RdbcDbType::Mysql => DataBase::Mysql,
+            RdbcDbType::Sqlite => DataBase::Sqlite,
+            RdbcDbType::Oracle => DataBase::Oracle,
+        }
+    }
+    /// 加载映射文件目录中的命名语句，返回加载的语句数量
+    /// 实例注册后仍可加载，加载失败时已加载的语句不变
+    pub fn load_mappers(&self, dir: impl AsRef<Path>) -> OrmResp<usize> {
+        self.mapper
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .load_dir(dir)
+    }
+    /// 读取已加载的命名语句，持有期间加载映射文件会等待，不要跨 await 持有
+    pub fn mapper(&self) -> RwLockReadGuard<'_, RdbcMapperRegistry> {
+        self.mapper.read().unwrap_or_else(|err| err.into_inner())
+    }
+    pub fn mapper_mut(&mut self) -> &mut RdbcMapperRegistry {
+        self.mapper.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
 }
 
 impl RdbcOrm {
@@ -38,31 +233,31 @@ impl RdbcOrm {
     where
         T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
     {
-        let row_page_data: PageData<RdbcOrmRow> = self
-            .pool
-            .find_page_by_query(query, page_num, page_size)
-            .await?;
-        let mut page_data = PageData::<T> {
-            page_num: row_page_data.page_num.clone(),
-            page_size: row_page_data.page_size.clone(),
-            total: row_page_data.total.clone(),
-            data: None,
-        };
-        if let Some(rows) = row_page_data.data {
-            let mut data = vec![];
-            for row in rows {
-                let t = T::from(row);
-                data.push(t);
+        let row_page_data: PageData<RdbcOrmRow> = match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_query(query, self.database());
+                ambient
+                    .find_page_by_sql(sql, params, page_num, page_size)
+                    .await?
             }
-            page_data.data = Some(data);
-        }
-        Ok(page_data)
+            None => {
+                self.read(|pool| pool.find_page_by_query(query, page_num, page_size))
+                    .await?
+            }
+        };
+        Ok(convert_page_data(row_page_data))
     }
     pub async fn find_list_by_query<T>(&self, query: &RdbcQueryWrapper) -> OrmResp<Vec<T>>
     where
         T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
     {
-        let rows = self.pool.find_list_by_query(query).await?;
+        let rows = match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_query(query, self.database());
+                ambient.find_list_by_sql(sql, params).await?
+            }
+            None => self.read(|pool| pool.find_list_by_query(query)).await?,
+        };
         let mut new_rows = vec![];
         for row in rows {
             let t = T::from(row);
@@ -74,7 +269,13 @@ impl RdbcOrm {
     where
         T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
     {
-        let row_op = self.pool.find_one_by_query(query).await?;
+        let row_op = match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_query(query, self.database());
+                ambient.find_one_by_sql(sql, params).await?
+            }
+            None => self.read(|pool| pool.find_one_by_query(query)).await?,
+        };
         if let Some(row) = row_op {
             let t = T::from(row);
             Ok(Some(t))
@@ -83,47 +284,296 @@ impl RdbcOrm {
         }
     }
     pub async fn execute_insert_by_wrapper(&self, insert: &RdbcInsertWrapper) -> OrmResp<usize> {
-        self.pool.execute_insert_by_wrapper(insert).await
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_insert(insert, self.database());
+                ambient.execute_sql(sql, params).await
+            }
+            None => self.pool.execute_insert_by_wrapper(insert).await,
+        }
     }
     pub async fn execute_update_by_wrapper(&self, update: &RdbcUpdateWrapper) -> OrmResp<usize> {
-        self.pool.execute_update_by_wrapper(update).await
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_update(update, self.database());
+                ambient.execute_sql(sql, params).await
+            }
+            None => self.pool.execute_update_by_wrapper(update).await,
+        }
     }
     pub async fn execute_delete_by_wrapper(&self, delete: &RdbcDeleteWrapper) -> OrmResp<usize> {
-        self.pool.execute_delete_by_wrapper(delete).await
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_delete(delete, self.database());
+                ambient.execute_sql(sql, params).await
+            }
+            None => self.pool.execute_delete_by_wrapper(delete).await,
+        }
+    }
+    pub async fn execute_ddl_by_wrapper(&self, ddl: &RdbcDdlWrapper) -> OrmResp<()> {
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => {
+                let (sql, params) = render_ddl(ddl, self.database());
+                ambient.execute_ddl(sql, params).await
+            }
+            None => self.pool.execute_ddl_by_wrapper(ddl).await,
+        }
     }
-    pub fn execute_ddl_by_wrapper(&self, ddl: &RdbcDdlWrapper) {}
 }
 
 impl RdbcOrm {
-    pub fn find_raw_page(
+    pub async fn find_raw_page<T>(
         &self,
         query: &String,
         params: Vec<RdbcValue>,
         page_num: usize,
         page_size: usize,
-    ) {
-    }
-    pub fn find_raw_list(&self, query: &String, params: Vec<RdbcValue>) {}
-    pub fn find_raw_one(&self, query: &String, params: Vec<RdbcValue>) {}
-    pub fn execute_raw_insert(&self, insert: &String, params: Vec<RdbcValue>) {}
-    pub fn execute_raw_update(&self, update: &String, params: Vec<RdbcValue>) {}
-    pub fn execute_raw_delete(&self, delete: &String, params: Vec<RdbcValue>) {}
-    pub fn execute_raw_ddl(&self, ddl: &String, params: Vec<RdbcValue>) {}
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let row_page_data = match self.ambient() {
+            Some(ambient) => {
+                ambient
+                    .find_page_by_sql(query.clone(), params, page_num, page_size)
+                    .await?
+            }
+            None => {
+                self.read(|pool| pool.find_page_by_sql(query, &params, page_num, page_size))
+                    .await?
+            }
+        };
+        Ok(convert_page_data(row_page_data))
+    }
+    pub async fn find_raw_list<T>(&self, query: &String, params: Vec<RdbcValue>) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let rows = match self.ambient() {
+            Some(ambient) => ambient.find_list_by_sql(query.clone(), params).await?,
+            None => {
+                self.read(|pool| pool.find_list_by_sql(query, &params))
+                    .await?
+            }
+        };
+        Ok(rows.into_iter().map(T::from).collect())
+    }
+    pub async fn find_raw_one<T>(
+        &self,
+        query: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let row_op = match self.ambient() {
+            Some(ambient) => ambient.find_one_by_sql(query.clone(), params).await?,
+            None => {
+                self.read(|pool| pool.find_one_by_sql(query, &params))
+                    .await?
+            }
+        };
+        Ok(row_op.map(T::from))
+    }
+    pub async fn execute_raw_insert(
+        &self,
+        insert: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(insert, params).await
+    }
+    pub async fn execute_raw_update(
+        &self,
+        update: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(update, params).await
+    }
+    pub async fn execute_raw_delete(
+        &self,
+        delete: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(delete, params).await
+    }
+    pub async fn execute_raw_ddl(&self, ddl: &String, params: Vec<RdbcValue>) -> OrmResp<()> {
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => ambient.execute_ddl(ddl.clone(), params).await,
+            None => self.pool.execute_ddl(ddl, &params).await,
+        }
+    }
+
+    async fn execute_sql(&self, sql: &String, params: Vec<RdbcValue>) -> OrmResp<usize> {
+        self.replicas.mark_write();
+        match self.ambient() {
+            Some(ambient) => ambient.execute_sql(sql.clone(), params).await,
+            None => self.pool.execute_sql(sql, &params).await,
+        }
+    }
 }
 
 impl RdbcOrm {
-    pub fn find_page_by_script(
+    pub async fn find_page_by_script<T>(
         &self,
         query: &String,
         params: HashMap<String, RdbcValue>,
         page_num: usize,
         page_size: usize,
-    ) {
-    }
-    pub fn find_list_by_script(&self, query: &String, params: HashMap<String, RdbcValue>) {}
-    pub fn find_one_by_script(&self, query: &String, params: HashMap<String, RdbcValue>) {}
-    pub fn execute_insert_script(&self, insert: &String, params: HashMap<String, RdbcValue>) {}
-    pub fn execute_update_script(&self, update: &String, params: HashMap<String, RdbcValue>) {}
-    pub fn execute_delete_script(&self, delete: &String, params: HashMap<String, RdbcValue>) {}
-    pub fn execute_ddl_script(&self, ddl: &String, params: HashMap<String, RdbcValue>) {}
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_page(&sql, values, page_num, page_size).await
+    }
+    pub async fn find_list_by_script<T>(
+        &self,
+        query: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_list(&sql, values).await
+    }
+    pub async fn find_one_by_script<T>(
+        &self,
+        query: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_one(&sql, values).await
+    }
+    pub async fn execute_insert_script(
+        &self,
+        insert: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(insert, &params)?;
+        self.execute_raw_insert(&sql, values).await
+    }
+    pub async fn execute_update_script(
+        &self,
+        update: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(update, &params)?;
+        self.execute_raw_update(&sql, values).await
+    }
+    pub async fn execute_delete_script(
+        &self,
+        delete: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(delete, &params)?;
+        self.execute_raw_delete(&sql, values).await
+    }
+    pub async fn execute_ddl_script(
+        &self,
+        ddl: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<()> {
+        let (sql, values) = self.render_script(ddl, &params)?;
+        self.execute_raw_ddl(&sql, values).await
+    }
+
+    /// 命名参数脚本转换为当前数据库的位置参数语句
+    fn render_script(
+        &self,
+        script: &String,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<(String, Vec<RdbcValue>)> {
+        RdbcScript::new(script.as_str(), &self.datasource.db_type).render(params)
+    }
+}
+
+impl RdbcOrm {
+    pub async fn find_page_by_name<T>(
+        &self,
+        name: &str,
+        params: HashMap<String, RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_statement(name, RdbcResultKind::Page, &params)?;
+        self.find_raw_page(&sql, values, page_num, page_size).await
+    }
+    pub async fn find_list_by_name<T>(
+        &self,
+        name: &str,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_statement(name, RdbcResultKind::List, &params)?;
+        self.find_raw_list(&sql, values).await
+    }
+    pub async fn find_one_by_name<T>(
+        &self,
+        name: &str,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_statement(name, RdbcResultKind::One, &params)?;
+        self.find_raw_one(&sql, values).await
+    }
+    pub async fn execute_by_name(
+        &self,
+        name: &str,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_statement(name, RdbcResultKind::Affected, &params)?;
+        let is_ddl = self.mapper().get(name)?.kind == RdbcStatementKind::Ddl;
+        if is_ddl {
+            self.execute_raw_ddl(&sql, values).await?;
+            return Ok(0);
+        }
+        self.execute_sql(&sql, values).await
+    }
+
+    /// 查找命名语句并生成位置参数语句，result 为调用方法对应的返回值类型
+    fn render_statement(
+        &self,
+        name: &str,
+        result: RdbcResultKind,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<(String, Vec<RdbcValue>)> {
+        let mapper = self.mapper();
+        let statement: &RdbcStatement = mapper.get(name)?;
+        let kind_matched = statement.is_query() != (result == RdbcResultKind::Affected);
+        let result_matched = statement.result.is_none_or(|declared| declared == result);
+        if !kind_matched || !result_matched {
+            return Err(OrmError::new(
+                OrmErrorKind::MapperError,
+                format!(
+                    "语句{}为{:?}类型，返回值为{:?}，不能通过该方法执行",
+                    name,
+                    statement.kind,
+                    statement.result_kind()
+                ),
+            ));
+        }
+        match statement.param_style {
+            RdbcParamStyle::Named => self.render_script(&statement.sql, params),
+            RdbcParamStyle::Positional => {
+                let values = statement.positional_values(params)?;
+                Ok((statement.sql.clone(), values))
+            }
+        }
+    }
 }
@@ -1,31 +1,83 @@
 use crate::bean::RdbcOrmRow;
 use crate::ds::{RdbcDataSource, RdbcDbType};
-use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::error::OrmResp;
 
-use crate::client::{build_postgres_pool, RdbcPostgresPool};
+use crate::client::{
+    build_mysql_pool, build_oracle_pool, build_postgres_pool, build_sqlite_pool, RdbcMysqlPool,
+    RdbcOraclePool, RdbcPostgresPool, RdbcSqlitePool,
+};
 use crate::{PageData, RdbcConn};
-use bmbp_sql::{RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper};
+use bmbp_sql::{
+    RdbcDdlWrapper, RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper,
+    RdbcValue,
+};
+use serde::Serialize;
 use std::sync::Arc;
 
+/// 连接池健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolHealth {
+    /// 本次检测能否取得连接并执行检测语句
+    pub healthy: bool,
+    pub max_size: usize,
+    /// 当前连接数，连接池不提供连接统计时为 None
+    pub total: Option<usize>,
+    /// 空闲连接数，连接池不提供连接统计时为 None
+    pub idle: Option<usize>,
+    pub in_use: usize,
+    /// 最近一次获取连接、建立连接或检测连接的错误
+    pub last_error: Option<String>,
+}
+
 pub enum RdbcPool {
     Postgres(RdbcPostgresPool),
+    Mysql(RdbcMysqlPool),
+    Sqlite(RdbcSqlitePool),
+    Oracle(RdbcOraclePool),
 }
 
 impl RdbcPool {
     pub async fn new(datasource: Arc<RdbcDataSource>) -> OrmResp<RdbcPool> {
         match datasource.db_type {
             RdbcDbType::Postgres => build_postgres_pool(datasource.clone()).await,
-            _ => {
-                return Err(OrmError {
-                    kind: OrmErrorKind::NotSupport,
-                    msg: "不支持的数据库类型".to_string(),
-                });
-            }
+            RdbcDbType::Mysql => build_mysql_pool(datasource.clone()).await,
+            RdbcDbType::Sqlite => build_sqlite_pool(datasource.clone()).await,
+            RdbcDbType::Oracle => build_oracle_pool(datasource.clone()).await,
+        }
+    }
+    /// 借出的连接数
+    pub fn in_use(&self) -> usize {
+        match self {
+            RdbcPool::Postgres(p) => p.in_use(),
+            RdbcPool::Mysql(p) => p.in_use(),
+            RdbcPool::Sqlite(p) => p.in_use(),
+            RdbcPool::Oracle(p) => p.in_use(),
+        }
+    }
+    /// 取出连接执行检测语句，并返回连接池状态
+    pub async fn health(&self) -> PoolHealth {
+        let check = match self.get_conn().await {
+            Ok(mut conn) => conn.validate().await,
+            Err(err) => Err(err),
+        };
+        let mut health = match self {
+            RdbcPool::Postgres(p) => p.health(),
+            RdbcPool::Mysql(p) => p.health(),
+            RdbcPool::Sqlite(p) => p.health(),
+            RdbcPool::Oracle(p) => p.health(),
+        };
+        if let Err(err) = check {
+            health.healthy = false;
+            health.last_error = Some(err.to_string());
         }
+        health
     }
-    pub async fn get_conn(&self) -> OrmResp<RdbcConn> {
+    pub async fn get_conn(&self) -> OrmResp<RdbcConn<'_>> {
         match self {
             RdbcPool::Postgres(p) => p.get_conn().await,
+            RdbcPool::Mysql(p) => p.get_conn().await,
+            RdbcPool::Sqlite(p) => p.get_conn().await,
+            RdbcPool::Oracle(p) => p.get_conn().await,
         }
     }
 
@@ -77,4 +129,44 @@ impl RdbcPool {
             .execute_delete_by_wrapper(update)
             .await
     }
+
+    pub(crate) async fn find_page_by_sql(
+        &self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        self.get_conn()
+            .await?
+            .find_page_by_sql(sql, params, page_num, page_size)
+            .await
+    }
+    pub(crate) async fn find_list_by_sql(
+        &self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        self.get_conn().await?.find_list_by_sql(sql, params).await
+    }
+    pub(crate) async fn find_one_by_sql(
+        &self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        self.get_conn().await?.find_one_by_sql(sql, params).await
+    }
+    pub(crate) async fn execute_sql(
+        &self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.get_conn().await?.execute_sql(sql, params).await
+    }
+    pub(crate) async fn execute_ddl_by_wrapper(&self, ddl: &RdbcDdlWrapper) -> OrmResp<()> {
+        self.get_conn().await?.execute_ddl_by_wrapper(ddl).await
+    }
+    pub(crate) async fn execute_ddl(&self, sql: &String, params: &Vec<RdbcValue>) -> OrmResp<()> {
+        self.get_conn().await?.execute_ddl(sql, params).await
+    }
 }
@@ -0,0 +1,272 @@
+use crate::config::config_error;
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{RdbcDataSource, RdbcOrm};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+/// init_bmbp_orm 注册的数据源名称
+pub const DEFAULT_DATASOURCE: &str = "default";
+
+/// 重新加载后等待原实例释放的时长，超时只记录日志
+const RELOAD_DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 已注册的数据源，锁只在读写注册表时持有，使用 RdbcOrm 期间不持有
+#[derive(Default)]
+struct OrmRegistry {
+    default: Option<String>,
+    orms: HashMap<String, Arc<RdbcOrm>>,
+}
+
+static REGISTRY: Lazy<RwLock<OrmRegistry>> = Lazy::new(|| RwLock::new(OrmRegistry::default()));
+
+fn read_registry() -> RwLockReadGuard<'static, OrmRegistry> {
+    REGISTRY.read().unwrap_or_else(|err| err.into_inner())
+}
+
+fn write_registry() -> RwLockWriteGuard<'static, OrmRegistry> {
+    REGISTRY.write().unwrap_or_else(|err| err.into_inner())
+}
+
+/// 创建并注册命名数据源，第一个注册的数据源作为默认数据源，名称已存在时报错
+pub async fn init_datasource(name: &str, ds: RdbcDataSource) -> OrmResp<Arc<RdbcOrm>> {
+    if read_registry().orms.contains_key(name) {
+        return Err(datasource_exists(name));
+    }
+    let orm = Arc::new(RdbcOrm::new(Arc::new(ds)).await?);
+    add_orm(name, orm.clone())?;
+    Ok(orm)
+}
+
+/// 注册已创建的 RdbcOrm，第一个注册的数据源作为默认数据源，名称已存在时报错
+pub fn add_orm(name: &str, orm: Arc<RdbcOrm>) -> OrmResp<()> {
+    let mut registry = write_registry();
+    if registry.orms.contains_key(name) {
+        return Err(datasource_exists(name));
+    }
+    registry.orms.insert(name.to_string(), orm);
+    if registry.default.is_none() {
+        registry.default = Some(name.to_string());
+    }
+    Ok(())
+}
+
+/// 移除命名数据源，正在使用该数据源的任务结束后连接池随之关闭
+/// 移除的是默认数据源时不再有默认数据源
+pub fn remove_datasource(name: &str) -> OrmResp<Arc<RdbcOrm>> {
+    let mut registry = write_registry();
+    let orm = registry
+        .orms
+        .remove(name)
+        .ok_or_else(|| datasource_not_found(name))?;
+    if registry.default.as_deref() == Some(name) {
+        registry.default = None;
+    }
+    Ok(orm)
+}
+
+/// 获取命名数据源
+pub fn orm(name: &str) -> OrmResp<Arc<RdbcOrm>> {
+    read_registry()
+        .orms
+        .get(name)
+        .cloned()
+        .ok_or_else(|| datasource_not_found(name))
+}
+
+/// 获取默认数据源
+pub fn default_orm() -> OrmResp<Arc<RdbcOrm>> {
+    let registry = read_registry();
+    let name = registry.default.as_deref().ok_or_else(|| {
+        config_error("未设置默认数据源，请先调用init_bmbp_orm或init_datasource".to_string())
+    })?;
+    registry
+        .orms
+        .get(name)
+        .cloned()
+        .ok_or_else(|| datasource_not_found(name))
+}
+
+/// 指定默认数据源
+pub fn set_default_datasource(name: &str) -> OrmResp<()> {
+    let mut registry = write_registry();
+    if !registry.orms.contains_key(name) {
+        return Err(datasource_not_found(name));
+    }
+    registry.default = Some(name.to_string());
+    Ok(())
+}
+
+/// 已注册的数据源名称
+pub fn datasource_names() -> Vec<String> {
+    let mut names: Vec<String> = read_registry().orms.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// 使用新的数据源配置重建命名数据源并替换，从库、读写分离配置及已加载的命名语句保留
+/// 替换后新的调用使用新连接池，执行中的调用仍使用原连接池，全部结束后原连接池关闭；
+/// 新实例沿用原实例的序号，任务中已开启的事务继续由原连接执行
+pub async fn reload_datasource(name: &str, ds: RdbcDataSource) -> OrmResp<()> {
+    let old = orm(name)?;
+    let new = old.rebuild(Arc::new(ds)).await?;
+    let old = replace_orm(name, Arc::new(new))?;
+    let name = name.to_string();
+    tokio::spawn(async move {
+        if !drain(&old, Instant::now() + RELOAD_DRAIN_TIMEOUT).await {
+            tracing::warn!(
+                "数据源{}的原实例仍被持有，借出连接{}个，连接池在全部释放后关闭",
+                name,
+                old.in_use()
+            );
+        }
+    });
+    Ok(())
+}
+
+/// 替换命名数据源，返回原实例，执行中的调用仍使用原实例
+pub fn replace_orm(name: &str, orm: Arc<RdbcOrm>) -> OrmResp<Arc<RdbcOrm>> {
+    let mut registry = write_registry();
+    match registry.orms.get_mut(name) {
+        Some(current) => Ok(std::mem::replace(current, orm)),
+        None => Err(datasource_not_found(name)),
+    }
+}
+
+/// 移除全部数据源，等待其它调用释放实例后关闭连接池
+/// 超时仍被持有时返回 Timeout 错误，连接池在实例全部释放后关闭
+pub async fn shutdown(timeout: Duration) -> OrmResp<()> {
+    let orms: Vec<(String, Arc<RdbcOrm>)> = {
+        let mut registry = write_registry();
+        registry.default = None;
+        registry.orms.drain().collect()
+    };
+    let deadline = Instant::now() + timeout;
+    let mut busy = vec![];
+    for (name, orm) in orms {
+        if !drain(&orm, deadline).await {
+            busy.push(format!("{}({})", name, orm.in_use()));
+        }
+    }
+    if busy.is_empty() {
+        Ok(())
+    } else {
+        busy.sort();
+        Err(OrmError::new(
+            OrmErrorKind::Timeout,
+            format!(
+                "关闭数据源超时，实例仍被持有(借出连接数): {}",
+                busy.join(",")
+            ),
+        ))
+    }
+}
+
+/// 等待实例不再被其它调用持有，之后释放实例即关闭连接池
+/// 借出的连接均归还时调用方仍可能持有实例并继续借出连接，因此只以引用计数判断
+async fn drain(orm: &Arc<RdbcOrm>, deadline: Instant) -> bool {
+    loop {
+        if Arc::strong_count(orm) == 1 {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// BMBP_ORM 的类型，保留原 OnceCell<RwLock<RdbcOrm>> 的 get、read、write 调用方式，均解析为当前的默认数据源
+/// read、write 返回共享的实例，不再能通过 write 获得可变引用，加载映射文件使用 RdbcOrm::load_mappers
+pub struct DefaultOrmCell;
+
+impl DefaultOrmCell {
+    /// 已设置默认数据源时返回自身
+    pub fn get(&self) -> Option<&DefaultOrmCell> {
+        default_orm().ok().map(|_| self)
+    }
+    /// 是否已设置默认数据源
+    pub fn initialized(&self) -> bool {
+        default_orm().is_ok()
+    }
+    /// 获取默认数据源
+    ///
+    /// # Panics
+    /// 未设置默认数据源时 panic，包括 get 之后默认数据源被移除的情况；不希望 panic 时使用 try_read
+    pub async fn read(&self) -> Arc<RdbcOrm> {
+        self.resolve()
+    }
+    /// 与 read 相同，返回共享的默认数据源
+    ///
+    /// # Panics
+    /// 未设置默认数据源时 panic
+    pub async fn write(&self) -> Arc<RdbcOrm> {
+        self.resolve()
+    }
+    /// 获取默认数据源，未设置时返回 ConfigError，与 default_orm 相同
+    pub async fn try_read(&self) -> OrmResp<Arc<RdbcOrm>> {
+        default_orm()
+    }
+    fn resolve(&self) -> Arc<RdbcOrm> {
+        match default_orm() {
+            Ok(orm) => orm,
+            Err(err) => panic!("{}", err.msg),
+        }
+    }
+}
+
+fn datasource_exists(name: &str) -> OrmError {
+    config_error(format!("数据源{}已注册", name))
+}
+
+fn datasource_not_found(name: &str) -> OrmError {
+    config_error(format!("数据源{}未注册", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn sqlite_orm() -> Arc<RdbcOrm> {
+        let ds = RdbcDataSource::from_url("sqlite::memory:").unwrap();
+        Arc::new(RdbcOrm::new(Arc::new(ds)).await.unwrap())
+    }
+
+    // 注册表为全局状态，在同一个测试中依次验证
+    #[tokio::test]
+    async fn register_and_default() {
+        assert!(default_orm().is_err());
+        assert!(DefaultOrmCell.try_read().await.is_err());
+        assert!(!DefaultOrmCell.initialized());
+
+        let first = sqlite_orm().await;
+        let second = sqlite_orm().await;
+        add_orm("first", first.clone()).unwrap();
+        add_orm("second", second.clone()).unwrap();
+        let err = add_orm("first", sqlite_orm().await).unwrap_err();
+        assert_eq!(err.kind, OrmErrorKind::ConfigError);
+        assert_eq!(datasource_names(), vec!["first", "second"]);
+
+        // 第一个注册的数据源为默认数据源
+        assert!(Arc::ptr_eq(&default_orm().unwrap(), &first));
+        assert!(Arc::ptr_eq(&DefaultOrmCell.read().await, &first));
+
+        assert!(set_default_datasource("missing").is_err());
+        set_default_datasource("second").unwrap();
+        assert!(Arc::ptr_eq(&default_orm().unwrap(), &second));
+        assert!(Arc::ptr_eq(&orm("first").unwrap(), &first));
+
+        // 移除默认数据源后不再有默认数据源，也不会回退到其它数据源
+        let removed = remove_datasource("second").unwrap();
+        assert!(Arc::ptr_eq(&removed, &second));
+        assert!(remove_datasource("second").is_err());
+        assert!(matches!(default_orm(), Err(err) if err.kind == OrmErrorKind::ConfigError));
+        assert!(DefaultOrmCell.get().is_none());
+        assert!(DefaultOrmCell.try_read().await.is_err());
+        assert!(orm("first").is_ok());
+
+        remove_datasource("first").unwrap();
+        assert!(datasource_names().is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::config::{deserialize_duration, deserialize_option_duration};
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::{PoolHealth, RdbcDataSource, RdbcPool};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    /// 当前任务强制从主库查询的 RdbcOrm 实例
+    static FORCE_PRIMARY: usize;
+}
+
+/// 从库选择方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalance {
+    /// 依次轮流使用各从库
+    #[default]
+    RoundRobin,
+    /// 优先使用借出连接最少的从库
+    LeastConnections,
+}
+
+/// 读写分离配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplicaConfig {
+    pub balance: LoadBalance,
+    /// 写入后该时长内的查询仍使用主库，避免因复制延迟读不到刚写入的数据
+    #[serde(deserialize_with = "deserialize_option_duration")]
+    pub primary_after_write: Option<Duration>,
+    /// 从库不可用时暂停使用的时长，期间查询由其它从库或主库执行
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub unhealthy_cooldown: Duration,
+}
+
+impl Default for ReplicaConfig {
+    fn default() -> Self {
+        ReplicaConfig {
+            balance: LoadBalance::RoundRobin,
+            primary_after_write: None,
+            unhealthy_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+struct RdbcReplica {
+    datasource: Arc<RdbcDataSource>,
+    pool: RdbcPool,
+    /// 暂停使用截止时间，为距 RdbcReplicas 创建的毫秒数，0 表示可用
+    down_until: AtomicU64,
+}
+
+/// 从库连接池，查询按配置选择可用的从库，没有可用从库时使用主库
+pub(crate) struct RdbcReplicas {
+    replicas: Vec<RdbcReplica>,
+    config: ReplicaConfig,
+    next: AtomicUsize,
+    started: Instant,
+    /// 最近一次写入时间，为距创建的毫秒数，0 表示未写入
+    last_write: AtomicU64,
+}
+
+impl RdbcReplicas {
+    pub(crate) async fn new(
+        primary: &RdbcDataSource,
+        datasources: Vec<Arc<RdbcDataSource>>,
+        config: ReplicaConfig,
+    ) -> OrmResp<Self> {
+        let mut replicas = Vec::with_capacity(datasources.len());
+        for datasource in datasources {
+            if datasource.db_type != primary.db_type {
+                return Err(OrmError::new(
+                    OrmErrorKind::ConfigError,
+                    format!(
+                        "从库{}的数据库类型与主库{:?}不一致",
+                        datasource, primary.db_type
+                    ),
+                ));
+            }
+            replicas.push(RdbcReplica {
+                pool: RdbcPool::new(datasource.clone()).await?,
+                datasource,
+                down_until: AtomicU64::new(0),
+            });
+        }
+        Ok(RdbcReplicas {
+            replicas,
+            config,
+            next: AtomicUsize::new(0),
+            started: Instant::now(),
+            last_write: AtomicU64::new(0),
+        })
+    }
+
+    /// 记录写入，配置了 primary_after_write 时之后的查询暂时使用主库
+    pub(crate) fn mark_write(&self) {
+        if self.config.primary_after_write.is_some() && !self.replicas.is_empty() {
+            self.last_write
+                .store(self.now_millis().max(1), Ordering::Relaxed);
+        }
+    }
+
+    /// 在从库执行查询，从库连接失败时暂停使用该从库并换用下一个，最终由主库执行
+    pub(crate) async fn read<'a, F, Fut, R>(
+        &'a self,
+        orm_id: usize,
+        primary: &'a RdbcPool,
+        f: F,
+    ) -> OrmResp<R>
+    where
+        F: Fn(&'a RdbcPool) -> Fut,
+        Fut: Future<Output = OrmResp<R>>,
+    {
+        if !self.use_replica(orm_id) {
+            return f(primary).await;
+        }
+        for index in self.candidates() {
+            let replica = &self.replicas[index];
+            match f(&replica.pool).await {
+                Err(err) if is_unavailable(&err) => {
+                    tracing::warn!("从库{}不可用，暂停使用: {}", index, err);
+                    let until =
+                        self.now_millis() + self.config.unhealthy_cooldown.as_millis() as u64;
+                    replica.down_until.store(until.max(1), Ordering::Relaxed);
+                }
+                rs => return rs,
+            }
+        }
+        f(primary).await
+    }
+
+    /// 从库数据源及读写分离配置，重建实例时使用
+    pub(crate) fn settings(&self) -> (Vec<Arc<RdbcDataSource>>, ReplicaConfig) {
+        let datasources = self
+            .replicas
+            .iter()
+            .map(|replica| replica.datasource.clone())
+            .collect();
+        (datasources, self.config.clone())
+    }
+
+    pub(crate) fn in_use(&self) -> usize {
+        self.replicas
+            .iter()
+            .map(|replica| replica.pool.in_use())
+            .sum()
+    }
+
+    pub(crate) async fn health(&self) -> Vec<PoolHealth> {
+        let mut health = Vec::with_capacity(self.replicas.len());
+        for replica in &self.replicas {
+            health.push(replica.pool.health().await);
+        }
+        health
+    }
+
+    fn use_replica(&self, orm_id: usize) -> bool {
+        if self.replicas.is_empty() || FORCE_PRIMARY.try_with(|id| *id == orm_id).unwrap_or(false) {
+            return false;
+        }
+        match self.config.primary_after_write {
+            Some(window) => {
+                let last_write = self.last_write.load(Ordering::Relaxed);
+                last_write == 0 || self.now_millis() >= last_write + window.as_millis() as u64
+            }
+            None => true,
+        }
+    }
+
+    /// 按选择方式排列的可用从库
+    fn candidates(&self) -> Vec<usize> {
+        let now = self.now_millis();
+        let count = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % count;
+        let mut candidates: Vec<usize> = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|index| self.replicas[*index].down_until.load(Ordering::Relaxed) <= now)
+            .collect();
+        if self.config.balance == LoadBalance::LeastConnections {
+            // 借出连接数相同时保持轮询顺序
+            candidates.sort_by_key(|index| self.replicas[*index].pool.in_use());
+        }
+        candidates
+    }
+
+    fn now_millis(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+}
+
+/// 当前任务中通过指定实例执行的查询均使用主库
+pub(crate) async fn force_primary<Fut: Future>(orm_id: usize, fut: Fut) -> Fut::Output {
+    FORCE_PRIMARY.scope(orm_id, fut).await
+}
+
+/// 获取连接失败或连接断开，获取连接超时没有数据库错误码
+fn is_unavailable(err: &OrmError) -> bool {
+    match err.kind {
+        OrmErrorKind::ConnError | OrmErrorKind::ConnectionLost | OrmErrorKind::PoolError => true,
+        OrmErrorKind::Timeout => err.code.is_none(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn replicas(count: usize, config: ReplicaConfig) -> (RdbcPool, RdbcReplicas) {
+        let datasource = Arc::new(RdbcDataSource::from_url("sqlite::memory:").unwrap());
+        let primary = RdbcPool::new(datasource.clone()).await.unwrap();
+        let replicas = RdbcReplicas::new(&datasource, vec![datasource.clone(); count], config)
+            .await
+            .unwrap();
+        (primary, replicas)
+    }
+
+    /// 执行查询，返回实际使用的从库序号，主库为 None；down 中的从库返回连接错误
+    async fn read(replicas: &RdbcReplicas, primary: &RdbcPool, down: &[usize]) -> Option<usize> {
+        replicas
+            .read(1, primary, |pool| {
+                let index = replicas
+                    .replicas
+                    .iter()
+                    .position(|replica| std::ptr::eq(&replica.pool, pool));
+                let unavailable = index.is_some_and(|index| down.contains(&index));
+                async move {
+                    if unavailable {
+                        Err(OrmError::new(OrmErrorKind::ConnError, "down".to_string()))
+                    } else {
+                        Ok(index)
+                    }
+                }
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_robin_order() {
+        let (primary, replicas) = replicas(3, ReplicaConfig::default()).await;
+        assert_eq!(replicas.candidates(), vec![0, 1, 2]);
+        assert_eq!(replicas.candidates(), vec![1, 2, 0]);
+        assert_eq!(read(&replicas, &primary, &[]).await, Some(2));
+        assert_eq!(read(&replicas, &primary, &[]).await, Some(0));
+    }
+
+    #[tokio::test]
+    async fn least_connections_order() {
+        let config = ReplicaConfig {
+            balance: LoadBalance::LeastConnections,
+            ..ReplicaConfig::default()
+        };
+        let (_primary, replicas) = replicas(3, config).await;
+        let _conn = replicas.replicas[0].pool.get_conn().await.unwrap();
+        assert_eq!(replicas.candidates(), vec![1, 2, 0]);
+        assert_eq!(replicas.candidates(), vec![1, 2, 0]);
+        assert_eq!(replicas.candidates(), vec![2, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn skips_replica_in_cooldown() {
+        let (primary, replicas) = replicas(2, ReplicaConfig::default()).await;
+        assert_eq!(read(&replicas, &primary, &[0]).await, Some(1));
+        for _ in 0..3 {
+            assert_eq!(replicas.candidates(), vec![1]);
+        }
+        assert_eq!(read(&replicas, &primary, &[]).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_primary_when_all_down() {
+        let (primary, replicas) = replicas(2, ReplicaConfig::default()).await;
+        assert_eq!(read(&replicas, &primary, &[0, 1]).await, None);
+        assert!(replicas.candidates().is_empty());
+        assert_eq!(read(&replicas, &primary, &[]).await, None);
+    }
+
+    #[tokio::test]
+    async fn primary_after_write_window() {
+        let config = ReplicaConfig {
+            primary_after_write: Some(Duration::from_millis(200)),
+            ..ReplicaConfig::default()
+        };
+        let (primary, replicas) = replicas(1, config).await;
+        assert_eq!(read(&replicas, &primary, &[]).await, Some(0));
+        replicas.mark_write();
+        assert_eq!(read(&replicas, &primary, &[]).await, None);
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(read(&replicas, &primary, &[]).await, Some(0));
+        assert_eq!(force_primary(1, read(&replicas, &primary, &[])).await, None);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::error::OrmError;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 事务重试策略
+/// 第 n 次重试前等待 initial_backoff * 2^(n-1)，不超过 max_backoff；
+/// 开启 jitter 时在 [0, 等待时间] 内随机取值，避免冲突的事务同时重试
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// 最多执行次数，包含第一次执行
+    pub max_attempts: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub jitter: bool,
+    /// 判断错误是否需要重试，默认重试串行化冲突及死锁
+    pub retry_if: Arc<dyn Fn(&OrmError) -> bool + Send + Sync>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            jitter: true,
+            retry_if: Arc::new(OrmError::is_retryable),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 attempt 次执行失败后的等待时间
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16) as u32;
+        let delay = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+        if self.jitter {
+            delay.mul_f64(random_ratio())
+        } else {
+            delay
+        }
+    }
+}
+
+/// [0, 1) 之间的随机数，RandomState 每次创建使用不同的随机种子
+fn random_ratio() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    hasher.write_u32(nanos);
+    (hasher.finish() % 10_000) as f64 / 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            jitter,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_until_capped() {
+        let policy = policy(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(64), Duration::from_secs(1));
+        assert_eq!(policy.backoff(usize::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_saturates_large_initial_delay() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::MAX,
+            max_backoff: Duration::from_secs(2),
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(30), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn jitter_stays_within_capped_delay() {
+        let policy = policy(true);
+        for attempt in 1..40 {
+            assert!(policy.backoff(attempt) <= Duration::from_secs(1));
+        }
+        assert!(policy.backoff(1) <= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,605 @@
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use bmbp_sql::RdbcValue;
+use std::collections::HashMap;
+
+/// 动态脚本支持的标签
+const DYNAMIC_TAGS: [&str; 5] = ["if", "foreach", "where", "set", "trim"];
+
+/// 动态脚本节点
+#[derive(Debug, Clone)]
+enum ScriptNode {
+    Text(String),
+    Tag {
+        name: String,
+        attrs: HashMap<String, String>,
+        children: Vec<ScriptNode>,
+    },
+}
+
+/// 动态脚本（MyBatis 风格）
+/// 支持以下标签，在绑定参数前根据参数值展开为普通命名参数脚本：
+/// - `<if test="name != null">...</if>` 参数存在且非空时保留片段，
+///   test 支持 `name`、`name != null`、`name == null`、`name != ''`、`name == ''`，以及 and/or 组合
+/// - `<foreach collection="ids" item="id" open="(" separator="," close=")">#{id}</foreach>` 展开列表参数
+/// - `<where>...</where>` 片段非空时补充 WHERE，并去除开头多余的 AND/OR
+/// - `<set>...</set>` 片段非空时补充 SET，并去除末尾多余的逗号
+/// - `<trim prefix="" suffix="" prefixOverrides="AND |OR " suffixOverrides=",">...</trim>`
+pub struct RdbcDynamicScript {
+    nodes: Vec<ScriptNode>,
+}
+
+/// 渲染过程中 foreach 生成的参数
+struct RenderContext {
+    generated: HashMap<String, RdbcValue>,
+    seq: usize,
+}
+
+impl RdbcDynamicScript {
+    pub fn parse(script: &str) -> OrmResp<Self> {
+        let mut stack: Vec<(String, HashMap<String, String>, Vec<ScriptNode>)> =
+            vec![("".to_string(), HashMap::new(), vec![])];
+        let mut text = String::new();
+        let mut rest = script;
+        while let Some(pos) = rest.find('<') {
+            text.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+            if let Some(name) = match_close_tag(rest) {
+                flush_text(&mut text, &mut stack);
+                let (open_name, attrs, children) = stack.pop().unwrap_or_default();
+                if open_name != name || stack.is_empty() {
+                    return Err(script_error(format!("动态脚本标签未正确闭合: </{}>", name)));
+                }
+                if let Some(parent) = stack.last_mut() {
+                    parent.2.push(ScriptNode::Tag {
+                        name: open_name,
+                        attrs,
+                        children,
+                    });
+                }
+                rest = &rest[name.len() + 3..];
+            } else if let Some((name, attrs, len)) = match_open_tag(rest)? {
+                flush_text(&mut text, &mut stack);
+                stack.push((name, attrs, vec![]));
+                rest = &rest[len..];
+            } else {
+                text.push('<');
+                rest = &rest[1..];
+            }
+        }
+        text.push_str(rest);
+        flush_text(&mut text, &mut stack);
+        if stack.len() != 1 {
+            let name = stack.last().map(|s| s.0.clone()).unwrap_or_default();
+            return Err(script_error(format!("动态脚本标签未闭合: <{}>", name)));
+        }
+        let nodes = stack.pop().map(|s| s.2).unwrap_or_default();
+        Ok(RdbcDynamicScript { nodes })
+    }
+
+    /// 根据参数展开动态标签，返回命名参数脚本及 foreach 生成的参数
+    pub fn render(
+        &self,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<(String, HashMap<String, RdbcValue>)> {
+        let mut ctx = RenderContext {
+            generated: HashMap::new(),
+            seq: 0,
+        };
+        let scope = HashMap::new();
+        let sql = render_nodes(&self.nodes, params, &scope, &mut ctx)?;
+        Ok((sql, ctx.generated))
+    }
+}
+
+fn script_error(msg: String) -> OrmError {
+    OrmError::new(OrmErrorKind::ScriptError, msg)
+}
+
+fn flush_text(text: &mut String, stack: &mut [(String, HashMap<String, String>, Vec<ScriptNode>)]) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(parent) = stack.last_mut() {
+        parent.2.push(ScriptNode::Text(std::mem::take(text)));
+    }
+}
+
+/// 匹配 `</name>`，返回标签名
+fn match_close_tag(src: &str) -> Option<String> {
+    let body = src.strip_prefix("</")?;
+    DYNAMIC_TAGS
+        .iter()
+        .find(|tag| body.starts_with(*tag) && body[tag.len()..].starts_with('>'))
+        .map(|tag| tag.to_string())
+}
+
+/// 开始标签的标签名、属性及标签长度
+type OpenTag = (String, HashMap<String, String>, usize);
+
+/// 匹配 `<name attr="value">`，返回标签名、属性及标签长度
+fn match_open_tag(src: &str) -> OrmResp<Option<OpenTag>> {
+    let body = &src[1..];
+    let tag = DYNAMIC_TAGS.iter().find(|tag| {
+        body.starts_with(*tag)
+            && body[tag.len()..]
+                .chars()
+                .next()
+                .map(|c| c == '>' || c.is_whitespace())
+                .unwrap_or(false)
+    });
+    let tag = match tag {
+        Some(tag) => *tag,
+        None => return Ok(None),
+    };
+    let mut attrs = HashMap::new();
+    let mut chars = body[tag.len()..].char_indices().peekable();
+    loop {
+        while chars
+            .peek()
+            .map(|(_, c)| c.is_whitespace())
+            .unwrap_or(false)
+        {
+            chars.next();
+        }
+        match chars.next() {
+            Some((idx, '>')) => {
+                return Ok(Some((tag.to_string(), attrs, 1 + tag.len() + idx + 1)));
+            }
+            Some((_, c)) => {
+                let mut key = c.to_string();
+                while let Some((_, c)) = chars.peek() {
+                    if *c == '=' || c.is_whitespace() {
+                        break;
+                    }
+                    key.push(*c);
+                    chars.next();
+                }
+                while chars
+                    .peek()
+                    .map(|(_, c)| c.is_whitespace())
+                    .unwrap_or(false)
+                {
+                    chars.next();
+                }
+                if chars.next().map(|(_, c)| c) != Some('=') {
+                    return Err(script_error(format!(
+                        "动态脚本属性格式错误: <{} {}",
+                        tag, key
+                    )));
+                }
+                while chars
+                    .peek()
+                    .map(|(_, c)| c.is_whitespace())
+                    .unwrap_or(false)
+                {
+                    chars.next();
+                }
+                let quote = match chars.next() {
+                    Some((_, q)) if q == '"' || q == '\'' => q,
+                    _ => {
+                        return Err(script_error(format!(
+                            "动态脚本属性值需使用引号: <{} {}",
+                            tag, key
+                        )));
+                    }
+                };
+                let mut value = String::new();
+                let mut closed = false;
+                for (_, c) in chars.by_ref() {
+                    if c == quote {
+                        closed = true;
+                        break;
+                    }
+                    value.push(c);
+                }
+                if !closed {
+                    return Err(script_error(format!(
+                        "动态脚本属性值未闭合: <{} {}",
+                        tag, key
+                    )));
+                }
+                attrs.insert(key, value);
+            }
+            None => {
+                return Err(script_error(format!("动态脚本标签未结束: <{}", tag)));
+            }
+        }
+    }
+}
+
+fn render_nodes(
+    nodes: &[ScriptNode],
+    params: &HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &mut RenderContext,
+) -> OrmResp<String> {
+    let mut sql = String::new();
+    for node in nodes {
+        match node {
+            ScriptNode::Text(text) => sql.push_str(rewrite_scope(text, scope).as_str()),
+            ScriptNode::Tag {
+                name,
+                attrs,
+                children,
+            } => {
+                let fragment = match name.as_str() {
+                    "if" => render_if(attrs, children, params, scope, ctx)?,
+                    "foreach" => render_foreach(attrs, children, params, scope, ctx)?,
+                    "where" => {
+                        let body = render_nodes(children, params, scope, ctx)?;
+                        trim_fragment(&body, "WHERE", "", &["AND", "OR"], &[])
+                    }
+                    "set" => {
+                        let body = render_nodes(children, params, scope, ctx)?;
+                        trim_fragment(&body, "SET", "", &[], &[","])
+                    }
+                    _ => {
+                        let body = render_nodes(children, params, scope, ctx)?;
+                        let prefix = attrs.get("prefix").map(|v| v.as_str()).unwrap_or("");
+                        let suffix = attrs.get("suffix").map(|v| v.as_str()).unwrap_or("");
+                        let prefix_overrides = split_overrides(attrs.get("prefixOverrides"));
+                        let suffix_overrides = split_overrides(attrs.get("suffixOverrides"));
+                        trim_fragment(
+                            &body,
+                            prefix,
+                            suffix,
+                            prefix_overrides.as_slice(),
+                            suffix_overrides.as_slice(),
+                        )
+                    }
+                };
+                sql.push_str(fragment.as_str());
+            }
+        }
+    }
+    Ok(sql)
+}
+
+fn render_if(
+    attrs: &HashMap<String, String>,
+    children: &[ScriptNode],
+    params: &HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &mut RenderContext,
+) -> OrmResp<String> {
+    let test = match attrs.get("test") {
+        Some(test) => test,
+        None => return Err(script_error("动态脚本<if>缺少test属性".to_string())),
+    };
+    if eval_test(test, params, scope, ctx)? {
+        render_nodes(children, params, scope, ctx)
+    } else {
+        Ok("".to_string())
+    }
+}
+
+fn render_foreach(
+    attrs: &HashMap<String, String>,
+    children: &[ScriptNode],
+    params: &HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &mut RenderContext,
+) -> OrmResp<String> {
+    let collection = match attrs.get("collection") {
+        Some(collection) => collection,
+        None => {
+            return Err(script_error(
+                "动态脚本<foreach>缺少collection属性".to_string(),
+            ))
+        }
+    };
+    let item = attrs.get("item").map(|v| v.as_str()).unwrap_or("item");
+    let items = match lookup(collection, params, scope, ctx) {
+        Some(RdbcValue::Array(values)) => values.clone(),
+        Some(RdbcValue::Null) | None => vec![],
+        Some(value) => vec![value.clone()],
+    };
+    if items.is_empty() {
+        return Ok("".to_string());
+    }
+    let mut parts = vec![];
+    for value in items {
+        ctx.seq += 1;
+        let generated_name = format!("__foreach_{}_{}", item, ctx.seq);
+        ctx.generated.insert(generated_name.clone(), value);
+        let mut item_scope = scope.clone();
+        item_scope.insert(item.to_string(), generated_name);
+        parts.push(
+            render_nodes(children, params, &item_scope, ctx)?
+                .trim()
+                .to_string(),
+        );
+    }
+    let open = attrs.get("open").map(|v| v.as_str()).unwrap_or("");
+    let close = attrs.get("close").map(|v| v.as_str()).unwrap_or("");
+    let separator = attrs.get("separator").map(|v| v.as_str()).unwrap_or("");
+    Ok(format!(" {}{}{} ", open, parts.join(separator), close))
+}
+
+/// 查找参数值，foreach 作用域内的变量优先
+fn lookup<'a>(
+    name: &str,
+    params: &'a HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &'a RenderContext,
+) -> Option<&'a RdbcValue> {
+    match scope.get(name) {
+        Some(generated_name) => ctx.generated.get(generated_name),
+        None => params.get(name),
+    }
+}
+
+/// 计算 test 表达式，or 优先级低于 and
+fn eval_test(
+    test: &str,
+    params: &HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &RenderContext,
+) -> OrmResp<bool> {
+    for or_part in split_keyword(test, "or") {
+        let mut matched = true;
+        for and_part in split_keyword(or_part.as_str(), "and") {
+            if !eval_condition(and_part.trim(), params, scope, ctx)? {
+                matched = false;
+                break;
+            }
+        }
+        if matched {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn eval_condition(
+    condition: &str,
+    params: &HashMap<String, RdbcValue>,
+    scope: &HashMap<String, String>,
+    ctx: &RenderContext,
+) -> OrmResp<bool> {
+    let (name, op, operand) = if let Some((name, operand)) = condition.split_once("!=") {
+        (name.trim(), "!=", operand.trim())
+    } else if let Some((name, operand)) = condition.split_once("==") {
+        (name.trim(), "==", operand.trim())
+    } else {
+        (condition.trim(), "!=", "null")
+    };
+    if name.is_empty() {
+        return Err(script_error(format!("动态脚本条件格式错误: {}", condition)));
+    }
+    let value = lookup(name, params, scope, ctx);
+    let is_null = matches!(value, None | Some(RdbcValue::Null));
+    let is_blank = match value {
+        Some(RdbcValue::Varchar(v)) => v.is_empty(),
+        Some(RdbcValue::Array(v)) => v.is_empty(),
+        _ => is_null,
+    };
+    let matched = match operand {
+        "null" => is_null,
+        "''" | "\"\"" => is_blank,
+        _ => {
+            return Err(script_error(format!(
+                "动态脚本条件仅支持与null或空字符串比较: {}",
+                condition
+            )));
+        }
+    };
+    Ok(if op == "!=" { !matched } else { matched })
+}
+
+/// 按空白包围的关键字拆分表达式
+fn split_keyword(expr: &str, keyword: &str) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = vec![];
+    for word in expr.split_whitespace() {
+        if word.eq_ignore_ascii_case(keyword) {
+            parts.push(current.join(" "));
+            current.clear();
+        } else {
+            current.push(word);
+        }
+    }
+    parts.push(current.join(" "));
+    parts
+}
+
+fn split_overrides(overrides: Option<&String>) -> Vec<&str> {
+    match overrides {
+        Some(overrides) => overrides
+            .split('|')
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// 去除片段首尾多余的关键字并补充前后缀，片段为空时返回空串
+fn trim_fragment(
+    body: &str,
+    prefix: &str,
+    suffix: &str,
+    prefix_overrides: &[&str],
+    suffix_overrides: &[&str],
+) -> String {
+    let mut body = body.trim();
+    for word in prefix_overrides {
+        let head = match body.get(..word.len()) {
+            Some(head) => head,
+            None => continue,
+        };
+        let rest = &body[word.len()..];
+        // 关键字后需为空白，避免误删 ORDER 之类以 OR 开头的单词
+        if head.eq_ignore_ascii_case(word)
+            && (word.chars().all(|c| !c.is_alphanumeric())
+                || rest
+                    .chars()
+                    .next()
+                    .map(|c| c.is_whitespace())
+                    .unwrap_or(true))
+        {
+            body = rest.trim_start();
+            break;
+        }
+    }
+    for word in suffix_overrides {
+        let split = match body.len().checked_sub(word.len()) {
+            Some(split) if body.is_char_boundary(split) => split,
+            _ => continue,
+        };
+        if body[split..].eq_ignore_ascii_case(word) {
+            body = body[..split].trim_end();
+            break;
+        }
+    }
+    if body.is_empty() {
+        return "".to_string();
+    }
+    format!(" {} {} {} ", prefix, body, suffix)
+}
+
+/// 将 foreach 作用域中的 #{item} 改写为生成的参数名
+fn rewrite_scope(text: &str, scope: &HashMap<String, String>) -> String {
+    if scope.is_empty() || !text.contains("#{") {
+        return text.to_string();
+    }
+    let mut sql = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("#{") {
+        sql.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        match rest.find('}') {
+            Some(end) => {
+                let name = rest[2..end].trim();
+                match scope.get(name) {
+                    Some(generated_name) => {
+                        sql.push_str(format!("#{{{}}}", generated_name).as_str());
+                    }
+                    None => sql.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    sql.push_str(rest);
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEARCH: &str = "SELECT * FROM t <where>\
+        <if test=\"name != null and name != ''\">AND name = #{name}</if>\
+        <if test=\"ids != null\">\
+        <foreach collection=\"ids\" item=\"id\" open=\"AND id IN (\" separator=\",\" close=\")\">#{id}</foreach>\
+        </if></where>";
+
+    fn render(
+        script: &str,
+        params: Vec<(&str, RdbcValue)>,
+    ) -> (String, HashMap<String, RdbcValue>) {
+        let params = params
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        let (sql, generated) = RdbcDynamicScript::parse(script)
+            .unwrap()
+            .render(&params)
+            .unwrap();
+        (
+            sql.split_whitespace().collect::<Vec<_>>().join(" "),
+            generated,
+        )
+    }
+
+    fn ids(values: &[i64]) -> RdbcValue {
+        RdbcValue::Array(values.iter().map(|v| RdbcValue::BigInt(*v)).collect())
+    }
+
+    #[test]
+    fn nested_if_and_foreach() {
+        let (sql, generated) = render(
+            SEARCH,
+            vec![
+                ("name", RdbcValue::Varchar("a".to_string())),
+                ("ids", ids(&[1, 2])),
+            ],
+        );
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE name = #{name} AND id IN (#{__foreach_id_1},#{__foreach_id_2})"
+        );
+        assert_eq!(generated.len(), 2);
+        assert!(matches!(
+            generated.get("__foreach_id_2"),
+            Some(RdbcValue::BigInt(2))
+        ));
+    }
+
+    #[test]
+    fn where_strips_leading_keyword() {
+        let (sql, _) = render(SEARCH, vec![("ids", ids(&[7]))]);
+        assert_eq!(sql, "SELECT * FROM t WHERE id IN (#{__foreach_id_1})");
+        let (sql, _) = render(SEARCH, vec![("name", RdbcValue::Varchar("".to_string()))]);
+        assert_eq!(sql, "SELECT * FROM t");
+    }
+
+    #[test]
+    fn empty_foreach_renders_nothing() {
+        let (sql, generated) = render(SEARCH, vec![("ids", ids(&[]))]);
+        assert_eq!(sql, "SELECT * FROM t");
+        assert!(generated.is_empty());
+    }
+
+    #[test]
+    fn nested_foreach_scopes_items() {
+        let script = "<foreach collection=\"groups\" item=\"g\" separator=\" OR \">\
+            (<if test=\"flag != null\">#{g} = #{flag}</if>)</foreach>";
+        let (sql, generated) = render(
+            script,
+            vec![("groups", ids(&[1, 2])), ("flag", RdbcValue::Boolean(true))],
+        );
+        assert_eq!(
+            sql,
+            "(#{__foreach_g_1} = #{flag}) OR (#{__foreach_g_2} = #{flag})"
+        );
+        assert_eq!(generated.len(), 2);
+    }
+
+    #[test]
+    fn set_and_trim() {
+        let script = "UPDATE t <set><if test=\"a != null\">a = #{a},</if>\
+            <if test=\"b != null\">b = #{b},</if></set>\
+            <trim prefix=\"WHERE\" prefixOverrides=\"AND |OR \">OR id = #{id}</trim>";
+        let (sql, _) = render(
+            script,
+            vec![("a", RdbcValue::Int(1)), ("id", RdbcValue::Int(2))],
+        );
+        assert_eq!(sql, "UPDATE t SET a = #{a} WHERE id = #{id}");
+    }
+
+    #[test]
+    fn or_has_lower_precedence_than_and() {
+        let script = "<if test=\"a != null and b != null or c != null\">x</if>";
+        let (sql, _) = render(script, vec![("c", RdbcValue::Int(1))]);
+        assert_eq!(sql, "x");
+        let (sql, _) = render(script, vec![("a", RdbcValue::Int(1))]);
+        assert_eq!(sql, "");
+    }
+
+    #[test]
+    fn unbalanced_tags_are_rejected() {
+        assert!(RdbcDynamicScript::parse("<if test=\"a\">x").is_err());
+        assert!(RdbcDynamicScript::parse("<where><if test=\"a\">x</where></if>").is_err());
+        assert!(RdbcDynamicScript::parse("x</if>").is_err());
+        assert!(RdbcDynamicScript::parse("<if test=a>x</if>").is_err());
+    }
+
+    #[test]
+    fn plain_comparisons_are_kept() {
+        let (sql, _) = render("SELECT * FROM t WHERE a < 1 AND b <> 2", vec![]);
+        assert_eq!(sql, "SELECT * FROM t WHERE a < 1 AND b <> 2");
+    }
+}
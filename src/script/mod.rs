@@ -0,0 +1,7 @@
+mod dynamic;
+mod named;
+mod split;
+
+pub use dynamic::*;
+pub use named::*;
+pub use split::*;
@@ -0,0 +1,194 @@
+use crate::ds::RdbcDbType;
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::script::split::{dollar_tag, find_char, find_seq};
+use crate::script::RdbcDynamicScript;
+use bmbp_sql::RdbcValue;
+use std::collections::HashMap;
+
+/// 命名参数脚本
+/// 将脚本中的 #{name} 占位符替换为目标数据库的位置参数，并按出现顺序绑定参数值
+/// 引号、注释及 PostgreSQL 美元引用中的内容保持原样，动态标签见 RdbcDynamicScript
+pub struct RdbcScript<'a> {
+    script: &'a str,
+    db_type: &'a RdbcDbType,
+}
+
+impl<'a> RdbcScript<'a> {
+    pub fn new(script: &'a str, db_type: &'a RdbcDbType) -> Self {
+        RdbcScript { script, db_type }
+    }
+
+    /// 先展开动态标签，再将命名参数替换为位置参数
+    pub fn render(&self, params: &HashMap<String, RdbcValue>) -> OrmResp<(String, Vec<RdbcValue>)> {
+        let (script, generated) = RdbcDynamicScript::parse(self.script)?.render(params)?;
+        if generated.is_empty() {
+            return self.bind(script.as_str(), params);
+        }
+        let mut merged = params.clone();
+        merged.extend(generated);
+        self.bind(script.as_str(), &merged)
+    }
+
+    fn bind(
+        &self,
+        script: &str,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<(String, Vec<RdbcValue>)> {
+        let chars: Vec<char> = script.chars().collect();
+        let mut sql = String::with_capacity(script.len());
+        let mut values = vec![];
+        let mut idx = 0;
+        while idx < chars.len() {
+            let c = chars[idx];
+            let skip_end = match c {
+                '\'' | '"' | '`' => {
+                    Some(find_char(&chars, idx + 1, c).map_or(chars.len(), |end| end + 1))
+                }
+                '-' if chars.get(idx + 1) == Some(&'-') => {
+                    Some(find_char(&chars, idx, '\n').unwrap_or(chars.len()))
+                }
+                '/' if chars.get(idx + 1) == Some(&'*') => {
+                    Some(find_seq(&chars, idx + 2, &['*', '/']).map_or(chars.len(), |end| end + 2))
+                }
+                '$' if *self.db_type == RdbcDbType::Postgres => {
+                    dollar_tag(&chars, idx).map(|tag| {
+                        find_seq(&chars, idx + tag.len(), &tag)
+                            .map_or(chars.len(), |end| end + tag.len())
+                    })
+                }
+                _ => None,
+            };
+            if let Some(end) = skip_end {
+                sql.extend(&chars[idx..end]);
+                idx = end;
+                continue;
+            }
+            if c != '#' || chars.get(idx + 1) != Some(&'{') {
+                sql.push(c);
+                idx += 1;
+                continue;
+            }
+            let end = find_char(&chars, idx + 2, '}');
+            let name: String = match end {
+                Some(end) => chars[idx + 2..end].iter().collect(),
+                None => String::new(),
+            };
+            let name = name.trim();
+            let end = match end {
+                Some(end) if !name.is_empty() => end,
+                _ => {
+                    return Err(OrmError::new(
+                        OrmErrorKind::ScriptError,
+                        format!("脚本占位符格式错误: 位置{}", idx),
+                    ));
+                }
+            };
+            match params.get(name) {
+                Some(value) => {
+                    values.push(value.clone());
+                    sql.push_str(self.placeholder(values.len()).as_str());
+                }
+                None => {
+                    return Err(OrmError::new(
+                        OrmErrorKind::ScriptError,
+                        format!("脚本参数缺失: {}", name),
+                    ));
+                }
+            }
+            idx = end + 1;
+        }
+        Ok((sql, values))
+    }
+
+    /// 第 index 个位置参数（从1开始）的占位符
+    fn placeholder(&self, index: usize) -> String {
+        match self.db_type {
+            RdbcDbType::Postgres => format!("${}", index),
+            RdbcDbType::Oracle => format!(":{}", index),
+            RdbcDbType::Mysql | RdbcDbType::Sqlite => "?".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(
+        script: &str,
+        db_type: RdbcDbType,
+        params: &[(&str, RdbcValue)],
+    ) -> OrmResp<(String, Vec<RdbcValue>)> {
+        let params = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect::<HashMap<_, _>>();
+        RdbcScript::new(script, &db_type).render(&params)
+    }
+
+    #[test]
+    fn placeholders_per_dialect() {
+        let script = "SELECT * FROM t WHERE a = #{a} AND b = #{ b }";
+        let params = [("a", RdbcValue::Int(1)), ("b", RdbcValue::Int(2))];
+        for (db_type, expected) in [
+            (
+                RdbcDbType::Postgres,
+                "SELECT * FROM t WHERE a = $1 AND b = $2",
+            ),
+            (RdbcDbType::Mysql, "SELECT * FROM t WHERE a = ? AND b = ?"),
+            (RdbcDbType::Sqlite, "SELECT * FROM t WHERE a = ? AND b = ?"),
+            (
+                RdbcDbType::Oracle,
+                "SELECT * FROM t WHERE a = :1 AND b = :2",
+            ),
+        ] {
+            let (sql, values) = render(script, db_type, &params).unwrap();
+            assert_eq!(sql, expected);
+            assert_eq!(values, vec![RdbcValue::Int(1), RdbcValue::Int(2)]);
+        }
+    }
+
+    #[test]
+    fn repeated_names_bind_in_order() {
+        let params = [("a", RdbcValue::Int(1)), ("b", RdbcValue::Int(2))];
+        let (sql, values) =
+            render("SELECT #{a}, #{b}, #{a}", RdbcDbType::Postgres, &params).unwrap();
+        assert_eq!(sql, "SELECT $1, $2, $3");
+        assert_eq!(
+            values,
+            vec![RdbcValue::Int(1), RdbcValue::Int(2), RdbcValue::Int(1)]
+        );
+    }
+
+    #[test]
+    fn missing_or_malformed_placeholder() {
+        let err = render("SELECT #{a}", RdbcDbType::Mysql, &[]).unwrap_err();
+        assert_eq!(err.kind, OrmErrorKind::ScriptError);
+        assert!(err.msg.contains('a'));
+        let err = render("SELECT #{a", RdbcDbType::Mysql, &[]).unwrap_err();
+        assert_eq!(err.kind, OrmErrorKind::ScriptError);
+    }
+
+    #[test]
+    fn literals_and_comments_are_kept() {
+        let script = "SELECT '#{a}', \"#{a}\" -- #{a}\n/* #{a} */ FROM t WHERE a = #{a}";
+        let (sql, values) = render(script, RdbcDbType::Mysql, &[("a", RdbcValue::Int(1))]).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT '#{a}', \"#{a}\" -- #{a}\n/* #{a} */ FROM t WHERE a = ?"
+        );
+        assert_eq!(values, vec![RdbcValue::Int(1)]);
+    }
+
+    #[test]
+    fn postgres_dollar_quoted_body_is_kept() {
+        let script = "DO $body$ BEGIN RAISE NOTICE '#{a}'; PERFORM #{a}; END $body$; SELECT #{a}";
+        let (sql, values) =
+            render(script, RdbcDbType::Postgres, &[("a", RdbcValue::Int(1))]).unwrap();
+        assert_eq!(
+            sql,
+            "DO $body$ BEGIN RAISE NOTICE '#{a}'; PERFORM #{a}; END $body$; SELECT $1"
+        );
+        assert_eq!(values, vec![RdbcValue::Int(1)]);
+    }
+}
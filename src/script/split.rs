@@ -0,0 +1,405 @@
+use crate::ds::RdbcDbType;
+
+/// 按分号拆分多语句脚本
+/// 引号、反引号、PostgreSQL 的 $tag$ 美元引用中的分号不作为分隔符，注释会被去除，空语句被忽略；
+/// MySQL/SQLite 的 BEGIN ... END、CASE ... END 块内的分号不作为分隔符，MySQL 支持 DELIMITER 指令修改分隔符；
+/// Oracle 的存储过程、函数、触发器、包及匿名块保留结尾的分号，脚本中有单独一行的 / 时块以 / 结束，
+/// 否则按 BEGIN/END 嵌套判断块的结束，包含嵌套子程序的块需使用 / 结束
+pub fn split_sql_statements(script: &str, db_type: &RdbcDbType) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let oracle = *db_type == RdbcDbType::Oracle;
+    let mut splitter = Splitter {
+        chars: &chars,
+        db_type,
+        statements: vec![],
+        current: String::new(),
+        delimiter: vec![';'],
+        depth: 0,
+        block_closed: false,
+        unit_closed: false,
+        slash_terminated: oracle && script.lines().any(|line| line.trim() == "/"),
+    };
+    splitter.split();
+    splitter.statements
+}
+
+/// Oracle PL/SQL 块类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlsqlKind {
+    /// 匿名块、存储过程、函数、触发器，最外层 BEGIN 对应的 END 结束
+    Routine,
+    /// 包、包体、类型体，不在 BEGIN 块内的 END 结束
+    Package,
+}
+
+struct Splitter<'a> {
+    chars: &'a [char],
+    db_type: &'a RdbcDbType,
+    statements: Vec<String>,
+    current: String,
+    delimiter: Vec<char>,
+    /// BEGIN/CASE 的嵌套层数
+    depth: usize,
+    /// 最外层 BEGIN 块已结束
+    block_closed: bool,
+    /// 出现了不在 BEGIN 块内的 END
+    unit_closed: bool,
+    /// Oracle 脚本使用单独一行的 / 结束 PL/SQL 块
+    slash_terminated: bool,
+}
+
+impl Splitter<'_> {
+    fn split(&mut self) {
+        let chars = self.chars;
+        let mut idx = 0;
+        while idx < chars.len() {
+            if self.delimiter != [';'] && chars[idx..].starts_with(&self.delimiter) {
+                self.push_statement();
+                idx += self.delimiter.len();
+                continue;
+            }
+            let c = chars[idx];
+            match c {
+                '\'' | '"' | '`' => {
+                    let end = find_char(chars, idx + 1, c).unwrap_or(chars.len() - 1);
+                    self.current.extend(&chars[idx..=end]);
+                    idx = end + 1;
+                }
+                '-' if chars.get(idx + 1) == Some(&'-') => {
+                    idx = find_char(chars, idx, '\n').unwrap_or(chars.len());
+                }
+                '/' if chars.get(idx + 1) == Some(&'*') => {
+                    idx = find_seq(chars, idx + 2, &['*', '/'])
+                        .map(|end| end + 2)
+                        .unwrap_or(chars.len());
+                }
+                '/' if *self.db_type == RdbcDbType::Oracle && is_slash_line(chars, idx) => {
+                    self.push_statement();
+                    idx = find_char(chars, idx, '\n').unwrap_or(chars.len());
+                }
+                '$' if *self.db_type == RdbcDbType::Postgres => match dollar_tag(chars, idx) {
+                    Some(tag) => {
+                        let body_start = idx + tag.len();
+                        let end = find_seq(chars, body_start, &tag)
+                            .map(|end| end + tag.len())
+                            .unwrap_or(chars.len());
+                        self.current.extend(&chars[idx..end]);
+                        idx = end;
+                    }
+                    None => {
+                        self.current.push(c);
+                        idx += 1;
+                    }
+                },
+                ';' if self.delimiter == [';'] => {
+                    self.semicolon();
+                    idx += 1;
+                }
+                c if c.is_alphanumeric() || c == '_' => {
+                    idx = self.word(idx);
+                }
+                _ => {
+                    self.current.push(c);
+                    idx += 1;
+                }
+            }
+        }
+        self.push_statement();
+    }
+
+    /// 处理单词，返回单词之后的位置
+    fn word(&mut self, start: usize) -> usize {
+        let chars = self.chars;
+        let end = word_end(chars, start);
+        let word: String = chars[start..end].iter().collect();
+        let statement_start = self.current.trim().is_empty();
+        let mut next = end;
+        match word.to_ascii_uppercase().as_str() {
+            "DELIMITER" if *self.db_type == RdbcDbType::Mysql && statement_start => {
+                let line_end = find_char(chars, end, '\n').unwrap_or(chars.len());
+                let delimiter: String = chars[end..line_end].iter().collect();
+                if !delimiter.trim().is_empty() {
+                    self.delimiter = delimiter.trim().chars().collect();
+                }
+                return line_end;
+            }
+            // MySQL/SQLite 语句开头的 BEGIN 为开启事务
+            "BEGIN" if *self.db_type == RdbcDbType::Oracle || !statement_start => {
+                self.depth += 1;
+            }
+            "CASE" => self.depth += 1,
+            "END" => {
+                let (after, after_end) = next_word(chars, end);
+                match after.as_str() {
+                    "IF" | "LOOP" | "WHILE" | "REPEAT" => {}
+                    _ => {
+                        if self.depth == 0 {
+                            self.unit_closed = true;
+                        } else {
+                            self.depth -= 1;
+                            self.block_closed |= self.depth == 0;
+                        }
+                        // END CASE 中的 CASE 不是新的 CASE 块
+                        if after == "CASE" {
+                            next = after_end;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.current.extend(&chars[start..next]);
+        next
+    }
+
+    fn semicolon(&mut self) {
+        match *self.db_type {
+            RdbcDbType::Mysql | RdbcDbType::Sqlite if self.depth > 0 => self.current.push(';'),
+            RdbcDbType::Oracle => match plsql_kind(&self.current) {
+                Some(kind) => {
+                    self.current.push(';');
+                    let closed = match kind {
+                        PlsqlKind::Routine => self.block_closed || self.unit_closed,
+                        PlsqlKind::Package => self.unit_closed,
+                    };
+                    if closed && !self.slash_terminated {
+                        self.push_statement();
+                    }
+                }
+                None => self.push_statement(),
+            },
+            _ => self.push_statement(),
+        }
+    }
+
+    fn push_statement(&mut self) {
+        let statement = self.current.trim();
+        if !statement.is_empty() {
+            self.statements.push(statement.to_string());
+        }
+        self.current.clear();
+        self.depth = 0;
+        self.block_closed = false;
+        self.unit_closed = false;
+    }
+}
+
+/// 根据语句开头的关键字判断是否为 PL/SQL 块
+fn plsql_kind(statement: &str) -> Option<PlsqlKind> {
+    let mut words = statement
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_uppercase());
+    match words.next()?.as_str() {
+        "DECLARE" | "BEGIN" => return Some(PlsqlKind::Routine),
+        "CREATE" => {}
+        _ => return None,
+    }
+    let mut word = words.next()?;
+    while matches!(
+        word.as_str(),
+        "OR" | "REPLACE" | "EDITIONABLE" | "NONEDITIONABLE"
+    ) {
+        word = words.next()?;
+    }
+    match word.as_str() {
+        "PROCEDURE" | "FUNCTION" | "TRIGGER" => Some(PlsqlKind::Routine),
+        "PACKAGE" => Some(PlsqlKind::Package),
+        "TYPE" if words.next()? == "BODY" => Some(PlsqlKind::Package),
+        _ => None,
+    }
+}
+
+fn word_end(chars: &[char], start: usize) -> usize {
+    (start..chars.len())
+        .find(|i| !(chars[*i].is_alphanumeric() || chars[*i] == '_'))
+        .unwrap_or(chars.len())
+}
+
+/// 跳过空白后的下一个单词（大写）及其结束位置
+fn next_word(chars: &[char], from: usize) -> (String, usize) {
+    let start = (from..chars.len())
+        .find(|i| !chars[*i].is_whitespace())
+        .unwrap_or(chars.len());
+    let end = word_end(chars, start);
+    let word: String = chars[start..end].iter().collect();
+    (word.to_ascii_uppercase(), end)
+}
+
+/// 所在行除 / 外只有空白
+fn is_slash_line(chars: &[char], idx: usize) -> bool {
+    let line_start = chars[..idx]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+    let line_end = find_char(chars, idx, '\n').unwrap_or(chars.len());
+    chars[line_start..idx].iter().all(|c| c.is_whitespace())
+        && chars[idx + 1..line_end].iter().all(|c| c.is_whitespace())
+}
+
+pub(super) fn find_char(chars: &[char], from: usize, target: char) -> Option<usize> {
+    (from..chars.len()).find(|i| chars[*i] == target)
+}
+
+pub(super) fn find_seq(chars: &[char], from: usize, seq: &[char]) -> Option<usize> {
+    if seq.is_empty() || chars.len() < seq.len() {
+        return None;
+    }
+    (from..=chars.len() - seq.len()).find(|i| &chars[*i..*i + seq.len()] == seq)
+}
+
+/// 识别 $$ 或 $tag$ 形式的美元引用开始标记，$1 之类的位置参数不是引用
+pub(super) fn dollar_tag(chars: &[char], start: usize) -> Option<Vec<char>> {
+    let mut end = start + 1;
+    while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    if end >= chars.len() || chars[end] != '$' {
+        return None;
+    }
+    if end > start + 1 && chars[start + 1].is_ascii_digit() {
+        return None;
+    }
+    Some(chars[start..=end].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(script: &str, db_type: RdbcDbType) -> Vec<String> {
+        split_sql_statements(script, &db_type)
+    }
+
+    #[test]
+    fn quotes_and_comments() {
+        let script = "-- 建表\nCREATE TABLE t (a VARCHAR(10) DEFAULT 'x;y');\n\
+            /* 注释; */ INSERT INTO t VALUES (\"a;b\");;";
+        assert_eq!(
+            split(script, RdbcDbType::Mysql),
+            vec![
+                "CREATE TABLE t (a VARCHAR(10) DEFAULT 'x;y')",
+                "INSERT INTO t VALUES (\"a;b\")"
+            ]
+        );
+    }
+
+    #[test]
+    fn postgres_dollar_quoted_body() {
+        let script =
+            "CREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 1; END; $$ LANGUAGE plpgsql;\n\
+            CREATE FUNCTION g() RETURNS int AS $body$ SELECT $1; $body$ LANGUAGE sql;\n\
+            SELECT $1;";
+        assert_eq!(
+            split(script, RdbcDbType::Postgres),
+            vec![
+                "CREATE FUNCTION f() RETURNS int AS $$ BEGIN RETURN 1; END; $$ LANGUAGE plpgsql",
+                "CREATE FUNCTION g() RETURNS int AS $body$ SELECT $1; $body$ LANGUAGE sql",
+                "SELECT $1"
+            ]
+        );
+    }
+
+    #[test]
+    fn mysql_procedure_body() {
+        let script =
+            "CREATE PROCEDURE p(IN n INT)\nBEGIN\n  IF n > 0 THEN\n    SELECT 1;\n  END IF;\n  \
+            CASE n WHEN 1 THEN SELECT 2; ELSE BEGIN SELECT 3; END; END CASE;\nEND;\n\
+            CREATE TABLE t (id INT);";
+        let statements = split(script, RdbcDbType::Mysql);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].starts_with("CREATE PROCEDURE p"));
+        assert!(statements[0].ends_with("END CASE;\nEND"));
+        assert_eq!(statements[1], "CREATE TABLE t (id INT)");
+    }
+
+    #[test]
+    fn mysql_delimiter() {
+        let script = "DELIMITER $$\nCREATE TRIGGER tr BEFORE INSERT ON t FOR EACH ROW\nBEGIN\n  \
+            SET NEW.a = 1;\nEND$$\nDELIMITER ;\nINSERT INTO t VALUES (1);";
+        assert_eq!(
+            split(script, RdbcDbType::Mysql),
+            vec![
+                "CREATE TRIGGER tr BEFORE INSERT ON t FOR EACH ROW\nBEGIN\n  SET NEW.a = 1;\nEND",
+                "INSERT INTO t VALUES (1)"
+            ]
+        );
+    }
+
+    #[test]
+    fn mysql_begin_transaction_is_not_block() {
+        assert_eq!(
+            split("BEGIN; UPDATE t SET a = 1; COMMIT;", RdbcDbType::Mysql),
+            vec!["BEGIN", "UPDATE t SET a = 1", "COMMIT"]
+        );
+    }
+
+    #[test]
+    fn sqlite_trigger_body() {
+        let script = "CREATE TRIGGER tr AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); \
+            UPDATE c SET n = CASE WHEN n IS NULL THEN 1 ELSE n + 1 END; END;\nSELECT 1;";
+        assert_eq!(
+            split(script, RdbcDbType::Sqlite),
+            vec![
+                "CREATE TRIGGER tr AFTER INSERT ON a BEGIN INSERT INTO b VALUES (1); \
+                UPDATE c SET n = CASE WHEN n IS NULL THEN 1 ELSE n + 1 END; END",
+                "SELECT 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn oracle_procedure_without_slash() {
+        let script = "CREATE TABLE t (id NUMBER);\n\
+            CREATE OR REPLACE PROCEDURE p IS\n  v NUMBER;\nBEGIN\n  \
+            IF v IS NULL THEN v := 1; END IF;\n  BEGIN NULL; END;\nEND p;\n\
+            INSERT INTO t VALUES (1);";
+        let statements = split(script, RdbcDbType::Oracle);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE t (id NUMBER)");
+        assert!(statements[1].starts_with("CREATE OR REPLACE PROCEDURE p IS"));
+        assert!(statements[1].ends_with("END p;"));
+        assert_eq!(statements[2], "INSERT INTO t VALUES (1)");
+    }
+
+    #[test]
+    fn oracle_package_without_slash() {
+        let script = "CREATE PACKAGE BODY pkg AS\n  PROCEDURE a IS BEGIN NULL; END a;\n  \
+            FUNCTION b RETURN NUMBER IS BEGIN RETURN 1; END b;\nEND pkg;\n\
+            DROP TABLE t;";
+        let statements = split(script, RdbcDbType::Oracle);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].ends_with("END b;\nEND pkg;"));
+        assert_eq!(statements[1], "DROP TABLE t");
+    }
+
+    #[test]
+    fn oracle_slash_terminated_blocks() {
+        let script = "CREATE OR REPLACE TRIGGER tr BEFORE INSERT ON t FOR EACH ROW\nDECLARE\n  \
+            PROCEDURE log IS BEGIN NULL; END log;\nBEGIN\n  log;\nEND;\n/\n\
+            BEGIN\n  DELETE FROM t WHERE a = 2 / 1;\nEND;\n/\nSELECT 1 FROM DUAL\n/\n";
+        let statements = split(script, RdbcDbType::Oracle);
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].ends_with("log;\nEND;"));
+        assert_eq!(
+            statements[1],
+            "BEGIN\n  DELETE FROM t WHERE a = 2 / 1;\nEND;"
+        );
+        assert_eq!(statements[2], "SELECT 1 FROM DUAL");
+    }
+
+    #[test]
+    fn oracle_plain_statements_drop_semicolon() {
+        assert_eq!(
+            split(
+                "SELECT CASE WHEN a = 1 THEN 'x' END FROM V$SESSION; COMMIT;",
+                RdbcDbType::Oracle
+            ),
+            vec![
+                "SELECT CASE WHEN a = 1 THEN 'x' END FROM V$SESSION",
+                "COMMIT"
+            ]
+        );
+    }
+}
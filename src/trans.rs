@@ -0,0 +1,496 @@
+use crate::bean::{convert_page_data, RdbcOrmRow};
+use crate::client::{
+    RdbcMysqlTransaction, RdbcOracleTransaction, RdbcPostgresTransaction, RdbcSqliteTransaction,
+};
+use crate::config::deserialize_option_duration;
+use crate::ds::RdbcDbType;
+use crate::error::{OrmError, OrmErrorKind, OrmResp};
+use crate::script::{split_sql_statements, RdbcScript};
+use crate::PageData;
+use bmbp_sql::{
+    render_ddl, render_delete, render_insert, render_query, render_update, DataBase,
+    RdbcDdlWrapper, RdbcDeleteWrapper, RdbcInsertWrapper, RdbcQueryWrapper, RdbcUpdateWrapper,
+    RdbcValue,
+};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// 事务闭包返回的 Future，借用事务直至执行完成
+pub type RdbcTransactionFuture<'t, R> = Pin<Box<dyn Future<Output = OrmResp<R>> + Send + 't>>;
+
+/// 事务隔离级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum IsolationLevel {
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+/// 开启事务的选项，未设置的项使用数据库默认值
+/// Postgres: 全部支持，语句超时通过 SET LOCAL statement_timeout 设置
+/// MySQL: 不支持 deferrable，语句超时通过 max_execution_time 设置，仅对查询生效
+/// Sqlite: 本身即为串行化，Serializable 使用 BEGIN IMMEDIATE 提前获取写锁；只读通过 query_only 实现；不支持 deferrable 及语句超时
+/// Oracle: 不支持 RepeatableRead，按 Serializable 处理；只读事务不能同时指定隔离级别；语句超时通过调用超时设置；不支持 deferrable
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TransactionOptions {
+    pub isolation_level: Option<IsolationLevel>,
+    pub read_only: bool,
+    /// 仅 Postgres 的 SERIALIZABLE READ ONLY 事务生效
+    pub deferrable: bool,
+    #[serde(default, deserialize_with = "deserialize_option_duration")]
+    pub statement_timeout: Option<Duration>,
+}
+
+/// 嵌套事务保存点序号
+static SAVEPOINT_SEQ: AtomicUsize = AtomicUsize::new(1);
+
+pub enum RdbcTransaction<'a> {
+    Postgres(RdbcPostgresTransaction<'a>),
+    Mysql(RdbcMysqlTransaction<'a>),
+    Sqlite(RdbcSqliteTransaction<'a>),
+    Oracle(RdbcOracleTransaction<'a>),
+}
+
+impl<'a> RdbcTransaction<'a> {
+    pub async fn commit(&mut self) -> OrmResp<()> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.commit().await,
+            RdbcTransaction::Mysql(c) => c.commit().await,
+            RdbcTransaction::Sqlite(c) => c.commit().await,
+            RdbcTransaction::Oracle(c) => c.commit().await,
+        }
+    }
+    pub async fn rollback(&mut self) -> OrmResp<()> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.rollback().await,
+            RdbcTransaction::Mysql(c) => c.rollback().await,
+            RdbcTransaction::Sqlite(c) => c.rollback().await,
+            RdbcTransaction::Oracle(c) => c.rollback().await,
+        }
+    }
+    /// 创建保存点
+    pub async fn savepoint(&mut self, name: &str) -> OrmResp<()> {
+        let sql = format!("SAVEPOINT {}", savepoint_name(name)?);
+        self.execute_command(sql.as_str()).await
+    }
+    /// 释放保存点，Oracle 不支持释放，保存点随事务结束自动失效
+    pub async fn release_savepoint(&mut self, name: &str) -> OrmResp<()> {
+        let sql = format!("RELEASE SAVEPOINT {}", savepoint_name(name)?);
+        match self {
+            RdbcTransaction::Oracle(_) => Ok(()),
+            _ => self.execute_command(sql.as_str()).await,
+        }
+    }
+    /// 回滚到保存点，保存点之前的修改保留，事务继续有效
+    pub async fn rollback_to_savepoint(&mut self, name: &str) -> OrmResp<()> {
+        let sql = format!("ROLLBACK TO SAVEPOINT {}", savepoint_name(name)?);
+        self.execute_command(sql.as_str()).await
+    }
+    /// 嵌套事务：在当前事务中创建保存点执行闭包
+    /// 返回 Ok 时释放保存点，返回 Err 或 panic 时回滚到保存点，外层事务不受影响
+    pub async fn transaction<F, R>(&mut self, f: F) -> OrmResp<R>
+    where
+        F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+    {
+        let name = format!("bmbp_sp_{}", SAVEPOINT_SEQ.fetch_add(1, Ordering::Relaxed));
+        self.savepoint(name.as_str()).await?;
+        let result = CatchUnwind(f(self)).await;
+        match result {
+            Ok(Ok(value)) => {
+                self.release_savepoint(name.as_str()).await?;
+                Ok(value)
+            }
+            Ok(Err(err)) => {
+                self.undo_savepoint(name.as_str()).await;
+                Err(err)
+            }
+            Err(panic) => {
+                self.undo_savepoint(name.as_str()).await;
+                resume_unwind(panic)
+            }
+        }
+    }
+    async fn undo_savepoint(&mut self, name: &str) {
+        let rs = match self.rollback_to_savepoint(name).await {
+            Ok(_) => self.release_savepoint(name).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = rs {
+            tracing::warn!("回滚到保存点{}失败: {}", name, err);
+        }
+    }
+    async fn execute_command(&mut self, sql: &str) -> OrmResp<()> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.execute_command(sql).await,
+            RdbcTransaction::Mysql(c) => c.execute_command(sql).await,
+            RdbcTransaction::Sqlite(c) => c.execute_command(sql).await,
+            RdbcTransaction::Oracle(c) => c.execute_command(sql).await,
+        }
+    }
+    /// 事务对应的 SQL 方言
+    pub(crate) fn database(&self) -> DataBase {
+        match self {
+            RdbcTransaction::Postgres(_) => DataBase::Postgres,
+            RdbcTransaction::Mysql(_) => DataBase::Mysql,
+            RdbcTransaction::Sqlite(_) => DataBase::Sqlite,
+            RdbcTransaction::Oracle(_) => DataBase::Oracle,
+        }
+    }
+    pub(crate) fn db_type(&self) -> RdbcDbType {
+        match self {
+            RdbcTransaction::Postgres(_) => RdbcDbType::Postgres,
+            RdbcTransaction::Mysql(_) => RdbcDbType::Mysql,
+            RdbcTransaction::Sqlite(_) => RdbcDbType::Sqlite,
+            RdbcTransaction::Oracle(_) => RdbcDbType::Oracle,
+        }
+    }
+    pub(crate) async fn find_page_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<RdbcOrmRow>> {
+        match self {
+            RdbcTransaction::Postgres(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcTransaction::Mysql(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcTransaction::Sqlite(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+            RdbcTransaction::Oracle(c) => {
+                c.find_page_by_sql_params(sql, params, page_num, page_size)
+                    .await
+            }
+        }
+    }
+    pub(crate) async fn find_list_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Vec<RdbcOrmRow>> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcTransaction::Mysql(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcTransaction::Sqlite(c) => c.find_list_by_sql_params(sql, params).await,
+            RdbcTransaction::Oracle(c) => c.find_list_by_sql_params(sql, params).await,
+        }
+    }
+    pub(crate) async fn find_one_by_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<Option<RdbcOrmRow>> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcTransaction::Mysql(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcTransaction::Sqlite(c) => c.find_one_by_sql_params(sql, params).await,
+            RdbcTransaction::Oracle(c) => c.find_one_by_sql_params(sql, params).await,
+        }
+    }
+    pub(crate) async fn execute_sql(
+        &mut self,
+        sql: &String,
+        params: &Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        match self {
+            RdbcTransaction::Postgres(c) => c.execute_sql_params(sql, params).await,
+            RdbcTransaction::Mysql(c) => c.execute_sql_params(sql, params).await,
+            RdbcTransaction::Sqlite(c) => c.execute_sql_params(sql, params).await,
+            RdbcTransaction::Oracle(c) => c.execute_sql_params(sql, params).await,
+        }
+    }
+}
+
+impl<'a> RdbcTransaction<'a> {
+    pub async fn find_page_by_query<T>(
+        &mut self,
+        query: &RdbcQueryWrapper,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, params) = render_query(query, self.database());
+        self.find_raw_page(&sql, params, page_num, page_size).await
+    }
+    pub async fn find_list_by_query<T>(&mut self, query: &RdbcQueryWrapper) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, params) = render_query(query, self.database());
+        self.find_raw_list(&sql, params).await
+    }
+    pub async fn find_one_by_query<T>(&mut self, query: &RdbcQueryWrapper) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, params) = render_query(query, self.database());
+        self.find_raw_one(&sql, params).await
+    }
+    pub async fn execute_insert_by_wrapper(
+        &mut self,
+        insert: &RdbcInsertWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_insert(insert, self.database());
+        self.execute_sql(&sql, &params).await
+    }
+    pub async fn execute_update_by_wrapper(
+        &mut self,
+        update: &RdbcUpdateWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_update(update, self.database());
+        self.execute_sql(&sql, &params).await
+    }
+    pub async fn execute_delete_by_wrapper(
+        &mut self,
+        delete: &RdbcDeleteWrapper,
+    ) -> OrmResp<usize> {
+        let (sql, params) = render_delete(delete, self.database());
+        self.execute_sql(&sql, &params).await
+    }
+    pub async fn execute_ddl_by_wrapper(&mut self, ddl: &RdbcDdlWrapper) -> OrmResp<()> {
+        let (sql, params) = render_ddl(ddl, self.database());
+        self.execute_raw_ddl(&sql, params).await
+    }
+}
+
+impl<'a> RdbcTransaction<'a> {
+    pub async fn find_raw_page<T>(
+        &mut self,
+        query: &String,
+        params: Vec<RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let row_page_data = self
+            .find_page_by_sql(query, &params, page_num, page_size)
+            .await?;
+        Ok(convert_page_data(row_page_data))
+    }
+    pub async fn find_raw_list<T>(
+        &mut self,
+        query: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let rows = self.find_list_by_sql(query, &params).await?;
+        Ok(rows.into_iter().map(T::from).collect())
+    }
+    pub async fn find_raw_one<T>(
+        &mut self,
+        query: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let row_op = self.find_one_by_sql(query, &params).await?;
+        Ok(row_op.map(T::from))
+    }
+    pub async fn execute_raw_insert(
+        &mut self,
+        insert: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(insert, &params).await
+    }
+    pub async fn execute_raw_update(
+        &mut self,
+        update: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(update, &params).await
+    }
+    pub async fn execute_raw_delete(
+        &mut self,
+        delete: &String,
+        params: Vec<RdbcValue>,
+    ) -> OrmResp<usize> {
+        self.execute_sql(delete, &params).await
+    }
+    /// 事务内逐条执行 DDL；MySQL/Oracle 的 DDL 会隐式提交当前事务
+    pub async fn execute_raw_ddl(&mut self, ddl: &String, params: Vec<RdbcValue>) -> OrmResp<()> {
+        let statements = split_sql_statements(ddl.as_str(), &self.db_type());
+        if !params.is_empty() && statements.len() != 1 {
+            return Err(OrmError::new(
+                OrmErrorKind::ScriptError,
+                "带参数的DDL只能包含一条语句".to_string(),
+            ));
+        }
+        for statement in statements.iter() {
+            self.execute_sql(statement, &params).await?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> RdbcTransaction<'a> {
+    pub async fn find_page_by_script<T>(
+        &mut self,
+        query: &String,
+        params: HashMap<String, RdbcValue>,
+        page_num: usize,
+        page_size: usize,
+    ) -> OrmResp<PageData<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_page(&sql, values, page_num, page_size).await
+    }
+    pub async fn find_list_by_script<T>(
+        &mut self,
+        query: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Vec<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_list(&sql, values).await
+    }
+    pub async fn find_one_by_script<T>(
+        &mut self,
+        query: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<Option<T>>
+    where
+        T: From<RdbcOrmRow> + Default + Debug + Clone + Serialize,
+    {
+        let (sql, values) = self.render_script(query, &params)?;
+        self.find_raw_one(&sql, values).await
+    }
+    pub async fn execute_insert_script(
+        &mut self,
+        insert: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(insert, &params)?;
+        self.execute_raw_insert(&sql, values).await
+    }
+    pub async fn execute_update_script(
+        &mut self,
+        update: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(update, &params)?;
+        self.execute_raw_update(&sql, values).await
+    }
+    pub async fn execute_delete_script(
+        &mut self,
+        delete: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<usize> {
+        let (sql, values) = self.render_script(delete, &params)?;
+        self.execute_raw_delete(&sql, values).await
+    }
+    pub async fn execute_ddl_script(
+        &mut self,
+        ddl: &String,
+        params: HashMap<String, RdbcValue>,
+    ) -> OrmResp<()> {
+        let (sql, values) = self.render_script(ddl, &params)?;
+        self.execute_raw_ddl(&sql, values).await
+    }
+
+    fn render_script(
+        &self,
+        script: &String,
+        params: &HashMap<String, RdbcValue>,
+    ) -> OrmResp<(String, Vec<RdbcValue>)> {
+        let db_type = self.db_type();
+        RdbcScript::new(script.as_str(), &db_type).render(params)
+    }
+}
+
+/// 在事务中执行闭包：返回 Ok 时提交，返回 Err 或发生 panic 时回滚
+/// panic 会在回滚后继续向上传播
+pub(crate) async fn run_in_transaction<F, R>(trans: &mut RdbcTransaction<'_>, f: F) -> OrmResp<R>
+where
+    F: for<'t, 'c> FnOnce(&'t mut RdbcTransaction<'c>) -> RdbcTransactionFuture<'t, R>,
+{
+    let result = CatchUnwind(f(trans)).await;
+    finish_transaction(trans, result).await
+}
+
+/// 根据闭包执行结果提交或回滚事务，panic 在回滚后继续传播
+pub(crate) async fn finish_transaction<R>(
+    trans: &mut RdbcTransaction<'_>,
+    result: Result<OrmResp<R>, Box<dyn Any + Send>>,
+) -> OrmResp<R> {
+    match result {
+        Ok(Ok(value)) => {
+            trans.commit().await?;
+            Ok(value)
+        }
+        Ok(Err(err)) => {
+            if let Err(rollback_err) = trans.rollback().await {
+                tracing::warn!("事务回滚失败: {}", rollback_err);
+            }
+            Err(err)
+        }
+        Err(panic) => {
+            if let Err(rollback_err) = trans.rollback().await {
+                tracing::warn!("事务回滚失败: {}", rollback_err);
+            }
+            resume_unwind(panic)
+        }
+    }
+}
+
+/// 保存点名称直接拼接到语句中，只允许字母、数字及下划线
+fn savepoint_name(name: &str) -> OrmResp<&str> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(OrmError::new(
+            OrmErrorKind::SqlError,
+            format!("无效的保存点名称: {}", name),
+        ))
+    }
+}
+
+/// 捕获 Future 执行过程中的 panic
+pub(crate) struct CatchUnwind<F>(pub(crate) F);
+
+impl<F> Future for CatchUnwind<F>
+where
+    F: Future + Unpin,
+{
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.0;
+        match catch_unwind(AssertUnwindSafe(|| Pin::new(inner).poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(value)) => Poll::Ready(Ok(value)),
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}